@@ -1,9 +1,15 @@
 // App state module - manages TUI application state
 
-use crate::config::Config;
-use crate::filter::{Filter, SortOrder};
-use crate::scanner::ClaudeFolder;
-use std::path::PathBuf;
+use crate::config::{Config, DisplayConfig};
+use crate::filter::{self, Filter, SortOrder};
+use crate::scanner::{self, ChildEntry, ClaudeFolder};
+use crate::trash::DeleteProgress;
+use crate::ui::{KeyMap, Theme};
+use ratatui::layout::Rect;
+use ratatui::widgets::ListState;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
 
 /// Application states
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -17,13 +23,62 @@ pub enum AppState {
 }
 
 /// Input mode for keyboard handling
-#[derive(Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum InputMode {
     #[default]
     Normal,
     Search,
+    /// Typing a minimum size threshold
+    MinSize,
 }
 
+/// Somewhere to copy text to (the system clipboard outside of tests)
+pub trait Clipboard {
+    fn set_text(&mut self, text: String) -> Result<(), arboard::Error>;
+}
+
+impl Clipboard for arboard::Clipboard {
+    fn set_text(&mut self, text: String) -> Result<(), arboard::Error> {
+        arboard::Clipboard::set_text(self, text)
+    }
+}
+
+fn system_clipboard() -> Option<Box<dyn Clipboard>> {
+    arboard::Clipboard::new()
+        .ok()
+        .map(|c| Box::new(c) as Box<dyn Clipboard>)
+}
+
+/// A deletion running in the background, shown while `Deleting`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeletionStatus {
+    pub total_folders: usize,
+    pub total_bytes: u64,
+    pub done: DeleteProgress,
+}
+
+impl DeletionStatus {
+    pub fn new(total_folders: usize, total_bytes: u64) -> Self {
+        Self {
+            total_folders,
+            total_bytes,
+            done: DeleteProgress::default(),
+        }
+    }
+
+    /// Fraction complete (0.0 - 1.0), by bytes or folders, whichever is further
+    pub fn ratio(&self) -> f64 {
+        let by_folders = self.done.folders as f64 / self.total_folders.max(1) as f64;
+        let by_bytes = self.done.bytes as f64 / self.total_bytes.max(1) as f64;
+        by_folders.max(by_bytes).min(1.0)
+    }
+}
+
+/// Folders that rank this high by size when found mid-scan are highlighted
+const TOP_ARRIVALS: usize = 5;
+/// How long a newly found large folder stays highlighted
+const ARRIVAL_HIGHLIGHT: Duration = Duration::from_secs(2);
+
 /// Main application state
 pub struct App {
     pub state: AppState,
@@ -31,20 +86,94 @@ pub struct App {
     pub selected_index: usize,
     pub scan_path: Option<PathBuf>,
     pub scan_complete: bool,
+    /// Paths skipped during the scan because they could not be read
+    pub scan_errors: Vec<(PathBuf, String)>,
+    /// How long the previous scan of the same root took
+    pub last_scan_duration: Option<Duration>,
+    /// Large folders found during the scan, with when they arrived
+    arrivals: Vec<(PathBuf, Instant)>,
     pub should_quit: bool,
     pub permanent_delete: bool,
+    /// Ask before deleting
+    pub confirm_delete: bool,
+    /// Ask before permanent deletes regardless of `confirm_delete`
+    pub confirm_permanent: bool,
+    /// Move deleted folders here instead of the Trash
+    pub quarantine_dir: Option<PathBuf>,
+    /// Archive folders here before permanently deleting them
+    pub backup_dir: Option<PathBuf>,
+    /// Skip the freshest folder of each sibling group when selecting all
+    pub keep_active: bool,
+    /// Working directory claudekill was started from
+    pub current_dir: Option<PathBuf>,
+    /// Folders at or under these paths can't be selected for deletion
+    pub protected_paths: Vec<PathBuf>,
+    /// Folders modified within this window need a second confirmation
+    pub recent_window: Option<Duration>,
+    /// The first confirmation of a deletion including recent folders was given
+    pub recent_confirmed: bool,
+    /// Folder being deleted on its own (`D`), in place of the selection
+    pub delete_target: Option<PathBuf>,
+    /// Selected folders that grew since the scan (`Some` once re-measured)
+    pub grown_since_scan: Option<Vec<PathBuf>>,
+    /// Pause before `y` is accepted in the confirm dialog for permanent deletes
+    pub permanent_confirm_delay: Duration,
+    /// When the confirm dialog was opened
+    pub confirm_opened_at: Option<Instant>,
     pub show_help: bool,
+    /// Show the detail pane for the highlighted folder
+    pub show_details: bool,
+    /// Vim-style count typed before a navigation key (`5j`)
+    pub pending_count: Option<usize>,
+    /// First visible line of the help overlay
+    pub help_scroll: u16,
     pub message: Option<String>,
     // Filter/search state
     pub filter: Filter,
     pub sort_order: SortOrder,
     pub input_mode: InputMode,
     pub search_input: String,
+    /// Size threshold being typed in `InputMode::MinSize`
+    pub size_input: String,
+    /// Query that was active when search mode was entered (restored on Esc)
+    search_before: Option<String>,
     pub show_filter_bar: bool,
+    /// Show on-disk size next to apparent size
+    pub show_disk_size: bool,
+    /// Show modification dates instead of relative ages
+    pub show_date_column: bool,
+    /// Sizes where the size column turns medium and large (None = no colors)
+    pub size_colors: Option<(u64, u64)>,
+    /// Frame counter driving the scan spinner
+    pub tick: usize,
+    /// Terminal height as last rendered
+    pub screen_height: u16,
+    /// Screen area of the folder list as last rendered
+    pub list_area: Rect,
+    /// Viewport of the folder list (scroll offset follows `selected_index`)
+    pub list_state: ListState,
+    /// Contents of the folder previewed in the confirm dialog (None while
+    /// they are still being measured)
+    pub confirm_preview: Option<(PathBuf, Option<Vec<ChildEntry>>)>,
+    /// Preview contents being measured on a worker thread
+    preview_rx: Option<Receiver<Vec<ChildEntry>>>,
+    /// History entries to keep when recording deletions (0 = unlimited)
+    pub max_history_entries: usize,
+    /// Paths that couldn't be copied to the clipboard, printed after exit
+    pub copied_paths: Vec<PathBuf>,
+    /// Kept open: on X11 the copied text only lives as long as the clipboard
+    clipboard: Option<Box<dyn Clipboard>>,
+    /// Opens the clipboard on first copy
+    open_clipboard: fn() -> Option<Box<dyn Clipboard>>,
+    /// Progress of the deletion in `AppState::Deleting`
+    pub deletion: Option<DeletionStatus>,
+    /// Colors used when rendering
+    pub theme: Theme,
+    /// Normal-mode key bindings
+    pub keymap: KeyMap,
 }
 
 impl App {
-    #[allow(dead_code)]
     pub fn new(permanent_delete: bool) -> Self {
         Self {
             state: AppState::Scanning,
@@ -52,41 +181,99 @@ impl App {
             selected_index: 0,
             scan_path: None,
             scan_complete: false,
+            scan_errors: Vec::new(),
+            arrivals: Vec::new(),
+            last_scan_duration: None,
             should_quit: false,
             permanent_delete,
+            confirm_delete: true,
+            confirm_permanent: true,
+            quarantine_dir: None,
+            backup_dir: None,
+            keep_active: false,
+            current_dir: std::env::current_dir().ok(),
+            protected_paths: Vec::new(),
+            recent_window: None,
+            recent_confirmed: false,
+            delete_target: None,
+            grown_since_scan: None,
+            permanent_confirm_delay: Duration::ZERO,
+            confirm_opened_at: None,
             show_help: false,
+            show_details: false,
+            pending_count: None,
+            help_scroll: 0,
             message: None,
             filter: Filter::default(),
             sort_order: SortOrder::default(),
             input_mode: InputMode::Normal,
             search_input: String::new(),
+            size_input: String::new(),
+            search_before: None,
             show_filter_bar: false,
+            show_disk_size: false,
+            show_date_column: false,
+            size_colors: DisplayConfig::default()
+                .size_color_thresholds()
+                .expect("default size thresholds are valid"),
+            tick: 0,
+            screen_height: 0,
+            list_area: Rect::default(),
+            list_state: ListState::default(),
+            confirm_preview: None,
+            preview_rx: None,
+            max_history_entries: crate::history::DEFAULT_MAX_HISTORY_ENTRIES,
+            copied_paths: Vec::new(),
+            clipboard: None,
+            open_clipboard: system_clipboard,
+            deletion: None,
+            theme: Theme::default(),
+            keymap: KeyMap::default(),
         }
     }
 
     /// Create App with config-based defaults
     pub fn new_with_config(permanent_delete: bool, config: &Config) -> Self {
-        Self {
-            state: AppState::Scanning,
-            folders: Vec::new(),
-            selected_index: 0,
-            scan_path: None,
-            scan_complete: false,
-            should_quit: false,
-            permanent_delete,
-            show_help: false,
-            message: None,
-            filter: Filter::default(),
+        let mut app = Self {
+            confirm_delete: config.behavior.confirm_delete,
+            confirm_permanent: config.behavior.confirm_permanent,
             sort_order: config.parse_sort_order(),
-            input_mode: InputMode::Normal,
-            search_input: String::new(),
             show_filter_bar: config.display.show_filter_bar,
-        }
+            show_disk_size: config.display.show_disk_size,
+            show_date_column: config.display.show_date_column,
+            protected_paths: config.safety.expanded_protected_paths(),
+            recent_window: config.safety.recent_window(),
+            max_history_entries: config.behavior.max_history_entries,
+            permanent_confirm_delay: Duration::from_secs(config.behavior.permanent_confirm_delay),
+            theme: Theme::from(&config.theme),
+            ..Self::new(permanent_delete)
+        };
+        app.filter.fuzzy = config.display.fuzzy_search;
+        app
     }
 
     pub fn add_folder(&mut self, folder: ClaudeFolder) {
+        let path = folder.path.clone();
+        let size = folder.size;
         self.folders.push(folder);
-        self.folders.sort_by(|a, b| b.size.cmp(&a.size));
+        self.folders.sort_by_key(|f| std::cmp::Reverse(f.size));
+
+        // Flag big hits while the scan is still running
+        if !self.scan_complete
+            && size > 0
+            && self.folders[..self.folders.len().min(TOP_ARRIVALS)]
+                .iter()
+                .any(|f| f.path == path)
+        {
+            self.arrivals.push((path, Instant::now()));
+        }
+    }
+
+    /// Whether a folder was found recently and ranks among the largest so far
+    pub fn is_new_arrival(&self, folder: &ClaudeFolder) -> bool {
+        self.arrivals
+            .iter()
+            .any(|(path, at)| *path == folder.path && at.elapsed() < ARRIVAL_HIGHLIGHT)
     }
 
     pub fn set_scanning(&mut self, path: PathBuf) {
@@ -98,6 +285,28 @@ impl App {
         self.state = AppState::Browsing;
     }
 
+    /// Drop the results for a fresh scan, keeping the filter and sort order
+    pub fn restart_scan(&mut self) {
+        self.folders.clear();
+        self.scan_errors.clear();
+        self.arrivals.clear();
+        self.scan_path = None;
+        self.scan_complete = false;
+        self.selected_index = 0;
+        *self.list_state.offset_mut() = 0;
+        self.state = AppState::Scanning;
+        self.message = None;
+    }
+
+    /// Stop scanning early and browse what was found so far
+    pub fn cancel_scan(&mut self) {
+        self.complete_scan();
+        self.message = Some(format!(
+            "Scan cancelled. Showing {} folder(s) found so far.",
+            self.folders.len()
+        ));
+    }
+
     pub fn move_up(&mut self) {
         if self.selected_index > 0 {
             self.selected_index -= 1;
@@ -123,6 +332,49 @@ impl App {
         self.selected_index = (self.selected_index + page_size).min(max_index);
     }
 
+    /// Move selection to the given row of the visible list (clamped)
+    pub fn go_to(&mut self, row: usize) {
+        let visible_count = self.visible_folder_indices().len();
+        self.selected_index = row.min(visible_count.saturating_sub(1));
+    }
+
+    /// Jump to the next (or previous) visible folder of the highlighted
+    /// folder's project type, wrapping around the list. The search already
+    /// narrows what's visible, so only the type is matched here
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let visible = self.visible_folder_indices();
+        let Some(&current) = visible.get(self.selected_index) else {
+            return;
+        };
+        let project_type = &self.folders[current].project_type;
+
+        let len = visible.len();
+        let found = (1..len)
+            .map(|step| {
+                if forward {
+                    (self.selected_index + step) % len
+                } else {
+                    (self.selected_index + len - step) % len
+                }
+            })
+            .find(|&row| self.folders[visible[row]].project_type == *project_type);
+
+        self.message = match found {
+            Some(row) => {
+                let wrapped = (row < self.selected_index) == forward;
+                self.selected_index = row;
+                wrapped.then(|| {
+                    format!(
+                        "Wrapped to the {} {} folder",
+                        if forward { "first" } else { "last" },
+                        project_type
+                    )
+                })
+            }
+            None => Some(format!("No more {} folders", project_type)),
+        };
+    }
+
     /// Move selection to first item
     pub fn go_home(&mut self) {
         self.selected_index = 0;
@@ -142,26 +394,234 @@ impl App {
         visible.get(self.selected_index).copied()
     }
 
+    /// The folder under the cursor
+    pub fn highlighted_folder(&self) -> Option<&ClaudeFolder> {
+        self.get_actual_folder_index()
+            .and_then(|idx| self.folders.get(idx))
+    }
+
+    /// Whether a folder is covered by `protected_paths`
+    pub fn is_protected(&self, folder: &ClaudeFolder) -> bool {
+        crate::trash::is_protected(&folder.path, &self.protected_paths)
+    }
+
+    /// Whether a folder changed recently enough to likely be in use
+    pub fn is_recent(&self, folder: &ClaudeFolder) -> bool {
+        crate::trash::is_recently_modified(folder.modified_at, self.recent_window)
+    }
+
+    /// Whether a folder is up for deletion: the `D` target if there is
+    /// one, otherwise the selection
+    fn is_targeted(&self, folder: &ClaudeFolder) -> bool {
+        match &self.delete_target {
+            Some(path) => folder.path == *path,
+            None => folder.selected,
+        }
+    }
+
+    /// Aim the next deletion at the highlighted folder only, leaving the
+    /// selection alone. Returns false if there is nothing deletable there
+    pub fn target_highlighted(&mut self) -> bool {
+        let Some(folder) = self
+            .get_actual_folder_index()
+            .and_then(|idx| self.folders.get(idx))
+        else {
+            return false;
+        };
+        if self.is_protected(folder) {
+            self.message = Some("This folder is protected and can't be deleted".to_string());
+            return false;
+        }
+        self.delete_target = Some(folder.path.clone());
+        true
+    }
+
+    /// Number of deletion targets that were modified recently
+    pub fn recent_target_count(&self) -> usize {
+        self.folders
+            .iter()
+            .filter(|f| self.is_targeted(f) && self.is_recent(f))
+            .count()
+    }
+
     pub fn toggle_selection(&mut self) {
         if let Some(actual_idx) = self.get_actual_folder_index() {
+            if self.is_protected(&self.folders[actual_idx]) {
+                self.message = Some("This folder is protected and can't be selected".to_string());
+                return;
+            }
             if let Some(folder) = self.folders.get_mut(actual_idx) {
                 folder.selected = !folder.selected;
             }
         }
     }
 
+    /// Open the highlighted folder's project directory in the file manager
+    pub fn open_highlighted(&mut self) {
+        let Some(folder) = self
+            .get_actual_folder_index()
+            .and_then(|idx| self.folders.get(idx))
+        else {
+            return;
+        };
+        let dir = folder.path.parent().unwrap_or(&folder.path).to_path_buf();
+        self.message = Some(match crate::utils::open_in_file_manager(&dir) {
+            Ok(()) => format!("Opened {}", dir.display()),
+            Err(e) => format!("Could not open file manager: {}", e),
+        });
+    }
+
+    /// Copy the highlighted folder's path to the clipboard, or remember it
+    /// for printing on exit when there's no clipboard (headless, SSH)
+    pub fn copy_highlighted_path(&mut self) {
+        let Some(path) = self
+            .get_actual_folder_index()
+            .and_then(|idx| self.folders.get(idx))
+            .map(|f| f.path.clone())
+        else {
+            return;
+        };
+
+        if self.clipboard.is_none() {
+            self.clipboard = (self.open_clipboard)();
+        }
+        let copied = match &mut self.clipboard {
+            Some(clipboard) => clipboard.set_text(path.display().to_string()),
+            None => Err(arboard::Error::ClipboardNotSupported),
+        };
+        self.message = Some(match copied {
+            Ok(()) => format!("Copied path: {}", path.display()),
+            Err(e) => {
+                self.copied_paths.push(path);
+                format!(
+                    "Clipboard unavailable ({}); path will be printed on exit",
+                    e
+                )
+            }
+        });
+    }
+
+    /// Show or hide the contents of the highlighted folder in the confirm
+    /// dialog (falls back to the first selected folder)
+    pub fn toggle_confirm_preview(&mut self) {
+        self.preview_rx = None;
+        if self.confirm_preview.take().is_some() {
+            return;
+        }
+        let highlighted = self
+            .get_actual_folder_index()
+            .and_then(|idx| self.folders.get(idx))
+            .filter(|f| self.is_targeted(f));
+        let target = highlighted.or_else(|| self.folders.iter().find(|f| self.is_targeted(f)));
+        if let Some(path) = target.map(|f| f.path.clone()) {
+            // Sizing subdirectories can take a while; on_tick picks them up
+            let (tx, rx) = channel();
+            let measured = path.clone();
+            std::thread::spawn(move || {
+                let _ = tx.send(scanner::list_children(&measured));
+            });
+            self.preview_rx = Some(rx);
+            self.confirm_preview = Some((path, None));
+        }
+    }
+
+    /// Select every visible folder (folders hidden by filters are left as they are)
     pub fn select_all(&mut self) {
-        for folder in &mut self.folders {
-            folder.selected = true;
+        let active = self.kept_active_paths();
+        let mut kept = 0;
+        for idx in self.visible_folder_indices() {
+            let folder = &mut self.folders[idx];
+            let is_active = active.contains(&folder.path);
+            kept += usize::from(is_active);
+            folder.selected =
+                !is_active && !crate::trash::is_protected(&folder.path, &self.protected_paths);
+        }
+
+        if kept > 0 {
+            self.message = Some(format!("Kept {} active folder(s) unselected", kept));
         }
     }
 
+    /// Deselect every folder, visible or not
     pub fn select_none(&mut self) {
         for folder in &mut self.folders {
             folder.selected = false;
         }
     }
 
+    /// Deselect the visible folders only
+    pub fn select_none_visible(&mut self) {
+        for idx in self.visible_folder_indices() {
+            self.folders[idx].selected = false;
+        }
+    }
+
+    /// Flip the selection of every visible folder (protected ones, and
+    /// active ones with `keep_active`, stay unselected)
+    pub fn invert_selection(&mut self) {
+        let active = self.kept_active_paths();
+        for idx in self.visible_folder_indices() {
+            let protected = self.is_protected(&self.folders[idx]);
+            let folder = &mut self.folders[idx];
+            folder.selected = !folder.selected && !protected && !active.contains(&folder.path);
+        }
+    }
+
+    /// Folders bulk selection leaves alone because they look in use
+    fn kept_active_paths(&self) -> std::collections::HashSet<PathBuf> {
+        if self.keep_active {
+            filter::active_folder_paths(&self.folders)
+        } else {
+            Default::default()
+        }
+    }
+
+    /// Deletion targets per project type as (type, count, size), largest first
+    pub fn targets_by_type(&self) -> Vec<(String, usize, u64)> {
+        let mut by_type: std::collections::HashMap<&str, (usize, u64)> =
+            std::collections::HashMap::new();
+        for folder in self.folders.iter().filter(|f| self.is_targeted(f)) {
+            let entry = by_type.entry(folder.project_type.as_str()).or_default();
+            entry.0 += 1;
+            entry.1 += folder.size;
+        }
+        let mut types: Vec<(String, usize, u64)> = by_type
+            .into_iter()
+            .map(|(name, (count, size))| (name.to_string(), count, size))
+            .collect();
+        types.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        types
+    }
+
+    /// Found folders inside a deletion target, which deleting it removes too
+    pub fn nested_in_targets(&self) -> usize {
+        let selected: Vec<&PathBuf> = self
+            .folders
+            .iter()
+            .filter(|f| self.is_targeted(f))
+            .map(|f| &f.path)
+            .collect();
+        self.folders
+            .iter()
+            .filter(|f| {
+                selected
+                    .iter()
+                    .any(|s| f.path != **s && f.path.starts_with(s))
+            })
+            .count()
+    }
+
+    /// Deletion targets currently hidden by filters
+    pub fn hidden_target_count(&self) -> usize {
+        let visible: std::collections::HashSet<usize> =
+            self.visible_folder_indices().into_iter().collect();
+        self.folders
+            .iter()
+            .enumerate()
+            .filter(|(idx, f)| self.is_targeted(f) && !visible.contains(idx))
+            .count()
+    }
+
     pub fn selected_count(&self) -> usize {
         self.folders.iter().filter(|f| f.selected).count()
     }
@@ -182,6 +642,28 @@ impl App {
         self.folders.iter().filter(|f| f.selected).collect()
     }
 
+    /// Folders the confirm dialog and deletion act on: the `D` target if
+    /// there is one, otherwise the selection
+    pub fn deletion_targets(&self) -> Vec<&ClaudeFolder> {
+        match &self.delete_target {
+            Some(path) => self.folders.iter().filter(|f| f.path == *path).collect(),
+            None => self.get_selected_folders(),
+        }
+    }
+
+    /// Combined size of the deletion targets
+    pub fn target_size(&self) -> u64 {
+        self.deletion_targets().iter().map(|f| f.size).sum()
+    }
+
+    /// The deletion target of the project in the current directory, if any
+    pub fn targeted_current_project(&self) -> Option<&ClaudeFolder> {
+        let cwd = resolve_path(self.current_dir.as_deref()?);
+        self.deletion_targets()
+            .into_iter()
+            .find(|f| f.path.parent().map(resolve_path).as_ref() == Some(&cwd))
+    }
+
     pub fn remove_deleted(&mut self, paths: &[PathBuf]) {
         self.folders.retain(|f| !paths.contains(&f.path));
         if self.selected_index >= self.folders.len() && !self.folders.is_empty() {
@@ -200,29 +682,13 @@ impl App {
             .collect();
 
         // Sort by current sort order
-        match self.sort_order {
-            SortOrder::SizeDesc => {
-                indices.sort_by(|&a, &b| self.folders[b].size.cmp(&self.folders[a].size))
-            }
-            SortOrder::SizeAsc => {
-                indices.sort_by(|&a, &b| self.folders[a].size.cmp(&self.folders[b].size))
-            }
-            SortOrder::NameAsc => {
-                indices.sort_by(|&a, &b| self.folders[a].path.cmp(&self.folders[b].path))
-            }
-            SortOrder::NameDesc => {
-                indices.sort_by(|&a, &b| self.folders[b].path.cmp(&self.folders[a].path))
-            }
-            SortOrder::DateDesc => indices.sort_by(|&a, &b| {
-                self.folders[b]
-                    .modified_at
-                    .cmp(&self.folders[a].modified_at)
-            }),
-            SortOrder::DateAsc => indices.sort_by(|&a, &b| {
-                self.folders[a]
-                    .modified_at
-                    .cmp(&self.folders[b].modified_at)
-            }),
+        indices.sort_by(|&a, &b| self.sort_order.compare(&self.folders[a], &self.folders[b]));
+
+        // Best fuzzy matches first while a fuzzy search is active
+        if self.filter.is_fuzzy_active() {
+            indices.sort_by_cached_key(|&i| {
+                std::cmp::Reverse(self.filter.fuzzy_score(&self.folders[i]))
+            });
         }
 
         indices
@@ -231,23 +697,153 @@ impl App {
     /// Enter search mode
     pub fn enter_search_mode(&mut self) {
         self.input_mode = InputMode::Search;
+        self.search_before = self.filter.search_query().map(str::to_string);
         self.search_input.clear();
     }
 
-    /// Exit search mode without applying
+    /// Exit search mode, restoring the query active before it was entered
     pub fn exit_search_mode(&mut self) {
+        let before = self.search_before.take();
+        self.keep_highlight(|app| app.filter.set_search(before));
         self.input_mode = InputMode::Normal;
     }
 
-    /// Apply search query and exit search mode
+    /// Filter the list by the search input as it is typed
+    pub fn update_search(&mut self) {
+        let query = (!self.search_input.is_empty()).then(|| self.search_input.clone());
+        self.keep_highlight(|app| app.filter.set_search(query));
+    }
+
+    /// Keep the current search query and exit search mode
     pub fn apply_search(&mut self) {
-        if self.search_input.is_empty() {
-            self.filter.search_query = None;
+        self.update_search();
+        self.search_before = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Project types present in the scan, sorted by name
+    pub fn project_types(&self) -> Vec<String> {
+        let mut types: Vec<String> = self
+            .folders
+            .iter()
+            .map(|f| f.project_type.clone())
+            .collect();
+        types.sort();
+        types.dedup();
+        types
+    }
+
+    /// Filter by the next project type found in the scan, then back to all
+    pub fn cycle_project_type(&mut self) {
+        let types = self.project_types();
+        let next = match self.filter.project_types.first() {
+            None => types.first(),
+            Some(current) => types
+                .iter()
+                .position(|t| t == current)
+                .and_then(|i| types.get(i + 1)),
+        };
+        let project_types = next.cloned().into_iter().collect();
+        self.keep_highlight(|app| app.filter.project_types = project_types);
+    }
+
+    /// Switch between substring and fuzzy search
+    pub fn toggle_fuzzy_search(&mut self) {
+        self.keep_highlight(|app| app.filter.fuzzy = !app.filter.fuzzy);
+        self.message = Some(if self.filter.fuzzy {
+            "Search mode: fuzzy".to_string()
         } else {
-            self.filter.search_query = Some(self.search_input.clone());
-        }
+            "Search mode: substring".to_string()
+        });
+    }
+
+    /// Start typing a minimum size threshold
+    pub fn enter_min_size_mode(&mut self) {
+        self.input_mode = InputMode::MinSize;
+        self.size_input.clear();
+    }
+
+    /// Leave the minimum size prompt without changing the filter
+    pub fn exit_min_size_mode(&mut self) {
         self.input_mode = InputMode::Normal;
-        self.selected_index = 0;
+    }
+
+    /// Parse the typed threshold into `filter.min_size` (empty clears it)
+    pub fn apply_min_size(&mut self) {
+        let min_size = if self.size_input.trim().is_empty() {
+            None
+        } else {
+            match crate::utils::parse_size(&self.size_input) {
+                Ok(bytes) => Some(bytes),
+                Err(e) => {
+                    self.message = Some(e.to_string());
+                    return;
+                }
+            }
+        };
+        self.keep_highlight(|app| app.filter.min_size = min_size);
+        self.message = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Apply a sort or filter change, keeping the cursor on the highlighted
+    /// folder if it's still visible (clamped to the list otherwise)
+    fn keep_highlight(&mut self, change: impl FnOnce(&mut Self)) {
+        let highlighted = self
+            .get_actual_folder_index()
+            .map(|i| self.folders[i].path.clone());
+        change(self);
+
+        let row = highlighted.and_then(|path| {
+            self.visible_folder_indices()
+                .iter()
+                .position(|&i| self.folders[i].path == path)
+        });
+        match row {
+            Some(row) => self.selected_index = row,
+            None => self.clamp_selection(),
+        }
+    }
+
+    /// Keep `selected_index` within the visible list
+    fn clamp_selection(&mut self) {
+        let visible_count = self.visible_count();
+        if self.selected_index >= visible_count {
+            self.selected_index = visible_count.saturating_sub(1);
+        }
+    }
+
+    /// Whether deleting the selection should go through the confirm dialog
+    pub fn needs_confirmation(&self) -> bool {
+        self.confirm_delete
+            || (self.permanent_delete && self.confirm_permanent)
+            || self.recent_target_count() > 0
+    }
+
+    /// Open the confirm dialog, starting the permanent-delete countdown
+    pub fn open_confirm(&mut self) {
+        self.state = AppState::Confirming;
+        self.confirm_opened_at = Some(Instant::now());
+    }
+
+    /// Time left before `y` confirms a permanent delete (trash isn't delayed)
+    pub fn confirm_countdown(&self) -> Option<Duration> {
+        if !self.permanent_delete || self.state != AppState::Confirming {
+            return None;
+        }
+        let elapsed = self.confirm_opened_at?.elapsed();
+        let remaining = self.permanent_confirm_delay.saturating_sub(elapsed);
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// Toggle between moving to Trash and permanent deletion
+    pub fn toggle_permanent_delete(&mut self) {
+        self.permanent_delete = !self.permanent_delete;
+        self.message = Some(if self.permanent_delete {
+            "Delete mode: PERMANENT (cannot be undone)".to_string()
+        } else {
+            "Delete mode: Trash".to_string()
+        });
     }
 
     /// Toggle filter bar visibility
@@ -257,14 +853,61 @@ impl App {
 
     /// Cycle through sort orders
     pub fn cycle_sort(&mut self) {
-        self.sort_order = self.sort_order.next();
+        self.keep_highlight(|app| app.sort_order = app.sort_order.next());
     }
 
     /// Clear all filters
     pub fn clear_filters(&mut self) {
-        self.filter.clear();
+        self.keep_highlight(|app| app.filter.clear());
         self.search_input.clear();
-        self.selected_index = 0;
+        self.size_input.clear();
+    }
+
+    /// Visible row index under a screen position inside the folder list
+    pub fn list_row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area;
+        // Skip the list's border
+        let inside = column > area.x
+            && column < area.right().saturating_sub(1)
+            && row > area.y
+            && row < area.bottom().saturating_sub(1);
+        if !inside {
+            return None;
+        }
+        let index = self.list_state.offset() + (row - area.y - 1) as usize;
+        (index < self.visible_count()).then_some(index)
+    }
+
+    /// Advance the animation frame counter
+    pub fn on_tick(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
+        self.arrivals
+            .retain(|(_, at)| at.elapsed() < ARRIVAL_HIGHLIGHT);
+
+        if let Some(children) = self.preview_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.preview_rx = None;
+            if let Some((_, pending @ None)) = &mut self.confirm_preview {
+                *pending = Some(children);
+            }
+        }
+    }
+
+    /// Current spinner frame shown while scanning
+    pub fn spinner(&self) -> char {
+        const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+        FRAMES[self.tick % FRAMES.len()]
+    }
+
+    /// Terminal window title summarizing the current results
+    pub fn window_title(&self) -> String {
+        if !self.scan_complete {
+            return "claudekill — scanning…".to_string();
+        }
+        format!(
+            "claudekill — {} folders, {}",
+            self.folders.len(),
+            crate::utils::format_size(self.total_size())
+        )
     }
 
     /// Get visible folder count (after filtering)
@@ -272,3 +915,353 @@ impl App {
         self.visible_folder_indices().len()
     }
 }
+
+/// Resolve `path` so relative scan roots like `.` compare equal to the cwd
+fn resolve_path(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_with(paths: &[&str]) -> App {
+        let mut app = App::new(false);
+        for (i, path) in paths.iter().enumerate() {
+            app.add_folder(ClaudeFolder {
+                path: PathBuf::from(path),
+                size: (i as u64 + 1) * 100,
+                ..Default::default()
+            });
+        }
+        app
+    }
+
+    #[test]
+    fn test_current_project_found_from_relative_scan_root() {
+        // `--path .` yields folders like `./.claude`
+        let mut app = app_with(&["/elsewhere/.claude", "./.claude"]);
+        app.current_dir = std::env::current_dir().ok();
+        app.toggle_selection();
+        assert_eq!(
+            app.targeted_current_project().map(|f| f.path.clone()),
+            Some(PathBuf::from("./.claude"))
+        );
+    }
+
+    #[test]
+    fn test_large_arrivals_highlighted_while_scanning() {
+        // Each folder is the largest so far when it arrives
+        let mut app = app_with(&["/a/.claude", "/b/.claude", "/c/.claude"]);
+        assert!(app.folders.iter().all(|f| app.is_new_arrival(f)));
+
+        // Small latecomers and folders found after the scan are not
+        let small = ClaudeFolder {
+            path: PathBuf::from("/small/.claude"),
+            size: 1,
+            ..Default::default()
+        };
+        for path in ["/d/.claude", "/e/.claude", "/f/.claude"] {
+            app.add_folder(ClaudeFolder {
+                path: PathBuf::from(path),
+                size: 1000,
+                ..Default::default()
+            });
+        }
+        app.add_folder(small.clone());
+        assert!(!app.is_new_arrival(&small));
+
+        app.complete_scan();
+        let late = ClaudeFolder {
+            path: PathBuf::from("/late/.claude"),
+            size: 5000,
+            ..Default::default()
+        };
+        app.add_folder(late.clone());
+        assert!(!app.is_new_arrival(&late));
+    }
+
+    #[test]
+    fn test_highlight_follows_folder_across_sort_and_filters() {
+        // Largest (last given) first: /c, /b, /a
+        let mut app = app_with(&["/a/.claude", "/b/.claude", "/c/.claude"]);
+        let highlighted = |app: &App| {
+            let row = app.visible_folder_indices()[app.selected_index];
+            app.folders[row].path.clone()
+        };
+
+        app.cycle_sort();
+        assert_eq!(app.sort_order, SortOrder::SizeAsc);
+        assert_eq!(app.selected_index, 2);
+        assert_eq!(highlighted(&app), PathBuf::from("/c/.claude"));
+
+        // Filtered out: the cursor is clamped instead
+        app.search_input = "b".to_string();
+        app.apply_search();
+        assert_eq!(app.selected_index, 0);
+        assert_eq!(highlighted(&app), PathBuf::from("/b/.claude"));
+
+        app.clear_filters();
+        assert_eq!(highlighted(&app), PathBuf::from("/b/.claude"));
+        assert_eq!(app.selected_index, 1);
+    }
+
+    #[test]
+    fn test_live_search_filters_and_reverts() {
+        let mut app = app_with(&["/a/web/.claude", "/a/api/.claude", "/b/docs/.claude"]);
+        app.filter.set_search(Some("a".to_string()));
+        app.selected_index = 1;
+
+        app.enter_search_mode();
+        app.search_input.push_str("docs");
+        app.update_search();
+        assert_eq!(app.visible_count(), 1);
+        assert_eq!(app.selected_index, 0);
+
+        app.exit_search_mode();
+        assert_eq!(app.filter.search_query(), Some("a"));
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        app.enter_search_mode();
+        app.search_input.push_str("web");
+        app.update_search();
+        app.apply_search();
+        assert_eq!(app.filter.search_query(), Some("web"));
+        assert_eq!(app.visible_count(), 1);
+    }
+
+    #[test]
+    fn test_selection_only_touches_visible_folders() {
+        // Sorted largest first: web, api, docs
+        let mut app = app_with(&["/b/docs/.claude", "/a/api/.claude", "/a/web/.claude"]);
+        app.folders[2].selected = true;
+        app.filter.set_search(Some("/a/".to_string()));
+
+        app.select_all();
+        assert_eq!(app.selected_count(), 3);
+        assert_eq!(app.hidden_target_count(), 1);
+
+        app.select_none_visible();
+        assert_eq!(app.selected_count(), 1);
+        assert!(app.folders[2].selected);
+
+        app.folders[0].selected = true;
+        app.invert_selection();
+        let selected: Vec<_> = app
+            .get_selected_folders()
+            .iter()
+            .map(|f| f.path.clone())
+            .collect();
+        assert_eq!(
+            selected,
+            vec![
+                PathBuf::from("/a/api/.claude"),
+                PathBuf::from("/b/docs/.claude")
+            ]
+        );
+
+        app.select_none();
+        assert_eq!(app.selected_count(), 0);
+    }
+
+    #[test]
+    fn test_keep_active_survives_select_all_and_invert() {
+        let now = std::time::SystemTime::now();
+        let mut app = app_with(&["/mono/a/.claude", "/mono/b/.claude", "/other/c/.claude"]);
+        for folder in &mut app.folders {
+            folder.modified_at = Some(now - Duration::from_secs(86_400));
+        }
+        app.folders[1].modified_at = Some(now);
+        app.keep_active = true;
+
+        app.select_all();
+        let unselected: Vec<&PathBuf> = app
+            .folders
+            .iter()
+            .filter(|f| !f.selected)
+            .map(|f| &f.path)
+            .collect();
+        assert_eq!(unselected, vec![&PathBuf::from("/mono/b/.claude")]);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Kept 1 active folder(s) unselected")
+        );
+
+        // Inverting never picks up the active folder either
+        app.invert_selection();
+        assert_eq!(app.selected_count(), 0);
+
+        // Active folders hidden by a filter aren't counted as kept
+        app.message = None;
+        app.filter.set_search(Some("/other/".to_string()));
+        app.select_all();
+        assert_eq!(app.selected_count(), 1);
+        assert_eq!(app.message, None);
+    }
+
+    #[test]
+    fn test_nested_in_targets() {
+        let mut app = app_with(&[
+            "/p/.claude/worktrees/x/.claude",
+            "/p/.claude",
+            "/p/sub/.claude",
+        ]);
+        assert_eq!(app.nested_in_targets(), 0);
+
+        app.folders[0].selected = true; // /p/sub/.claude
+        assert_eq!(app.nested_in_targets(), 0);
+
+        app.folders[1].selected = true; // /p/.claude
+        assert_eq!(app.nested_in_targets(), 1);
+    }
+
+    #[test]
+    fn test_fuzzy_search_ranks_by_score() {
+        // Largest first by default; the better match is the smaller folder
+        let mut app = app_with(&["/src/apps/web/.claude", "/app/.claude"]);
+        app.toggle_fuzzy_search();
+        app.filter.set_search(Some("app".to_string()));
+
+        let visible: Vec<_> = app
+            .visible_folder_indices()
+            .into_iter()
+            .map(|i| app.folders[i].path.clone())
+            .collect();
+        assert_eq!(visible[0], PathBuf::from("/app/.claude"));
+        assert_eq!(visible.len(), 2);
+    }
+
+    #[test]
+    fn test_confirm_preview_measured_in_background() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("app/.claude");
+        std::fs::create_dir_all(path.join("logs")).unwrap();
+        std::fs::write(path.join("logs/a.log"), vec![b'x'; 50]).unwrap();
+
+        let mut app = App::new(false);
+        app.add_folder(ClaudeFolder {
+            path: path.clone(),
+            selected: true,
+            ..Default::default()
+        });
+        app.toggle_confirm_preview();
+        assert_eq!(app.confirm_preview, Some((path.clone(), None)));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while matches!(app.confirm_preview, Some((_, None))) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+            app.on_tick();
+        }
+        let children = app.confirm_preview.and_then(|(_, c)| c).unwrap();
+        assert_eq!((children[0].name.as_str(), children[0].size), ("logs", 50));
+    }
+
+    #[test]
+    fn test_open_highlighted_without_folders() {
+        let mut app = App::new(false);
+        app.open_highlighted();
+        app.copy_highlighted_path();
+        assert!(app.message.is_none());
+        assert!(app.copied_paths.is_empty());
+    }
+
+    struct FakeClipboard;
+
+    impl Clipboard for FakeClipboard {
+        fn set_text(&mut self, _text: String) -> Result<(), arboard::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_copy_path_falls_back_without_clipboard() {
+        let mut app = app_with(&["/p/.claude"]);
+        app.open_clipboard = || None;
+        app.copy_highlighted_path();
+        assert!(app.message.unwrap().starts_with("Clipboard unavailable"));
+        assert_eq!(app.copied_paths, vec![PathBuf::from("/p/.claude")]);
+    }
+
+    #[test]
+    fn test_copy_path_to_clipboard() {
+        let mut app = app_with(&["/p/.claude"]);
+        app.open_clipboard = || Some(Box::new(FakeClipboard));
+        app.copy_highlighted_path();
+        assert_eq!(app.message.as_deref(), Some("Copied path: /p/.claude"));
+        assert!(app.copied_paths.is_empty());
+    }
+
+    #[test]
+    fn test_targets_by_type() {
+        let mut app = app_with(&["/a/.claude", "/b/.claude", "/c/.claude", "/d/.claude"]);
+        for (folder, kind) in app
+            .folders
+            .iter_mut()
+            .zip(["Rust", "Node.js", "Rust", "Go"])
+        {
+            folder.project_type = kind.to_string();
+        }
+        assert!(app.targets_by_type().is_empty());
+
+        // Sizes are 400, 300, 200 and 100
+        app.folders[0].selected = true;
+        app.folders[1].selected = true;
+        app.folders[2].selected = true;
+        assert_eq!(
+            app.targets_by_type(),
+            vec![
+                ("Rust".to_string(), 2, 600),
+                ("Node.js".to_string(), 1, 300),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jump_to_match() {
+        // Largest first: rows are /d (Rust), /c (Go), /b (Python), /a (Rust)
+        let mut app = app_with(&["/a/.claude", "/b/.claude", "/c/.claude", "/d/.claude"]);
+        for (folder, kind) in app.folders.iter_mut().zip(["Rust", "Go", "Python", "Rust"]) {
+            folder.project_type = kind.to_string();
+        }
+
+        app.jump_to_match(true);
+        assert_eq!(app.selected_index, 3);
+        assert!(app.message.is_none());
+
+        // Wraps around in both directions
+        app.jump_to_match(true);
+        assert_eq!(app.selected_index, 0);
+        assert!(app.message.as_deref().unwrap().starts_with("Wrapped"));
+        app.jump_to_match(false);
+        assert_eq!(app.selected_index, 3);
+
+        // The only folder of its type stays put
+        app.selected_index = 1;
+        app.jump_to_match(true);
+        assert_eq!(app.selected_index, 1);
+        assert_eq!(app.message.as_deref(), Some("No more Go folders"));
+    }
+
+    #[test]
+    fn test_deletion_status_ratio() {
+        let mut status = DeletionStatus::new(4, 1000);
+        assert_eq!(status.ratio(), 0.0);
+
+        // Bytes move the bar within a single large folder
+        status.done.bytes = 500;
+        assert_eq!(status.ratio(), 0.5);
+
+        // Trash moves only report folders
+        status.done = DeleteProgress {
+            folders: 3,
+            ..Default::default()
+        };
+        assert_eq!(status.ratio(), 0.75);
+
+        // Apparent bytes can exceed a block-accounted total
+        status.done.bytes = 5000;
+        assert_eq!(status.ratio(), 1.0);
+    }
+}
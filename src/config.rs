@@ -3,8 +3,10 @@
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Main configuration structure
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -13,18 +15,137 @@ pub struct Config {
     pub scan: ScanConfig,
     pub display: DisplayConfig,
     pub behavior: BehaviorConfig,
+    pub project: ProjectConfig,
+    pub safety: SafetyConfig,
+    pub report: ReportConfig,
+    pub theme: ThemeConfig,
+    /// Action name -> space-separated keys (e.g. `delete = "x"`)
+    pub keybinds: BTreeMap<String, String>,
 }
 
 /// Scan-related configuration
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(default)]
 pub struct ScanConfig {
-    /// Default paths to scan (empty = home directory)
+    /// Default paths to scan, all of them in one pass (empty = home directory)
     pub default_paths: Vec<PathBuf>,
-    /// Patterns to exclude from scanning
+    /// Patterns to exclude from scanning (globs like `**/target/**`, or substrings)
     pub exclude_patterns: Vec<String>,
     /// Include global ~/.claude folder
     pub include_global: bool,
+    /// Maximum directory depth to descend (unset = unlimited)
+    pub max_depth: Option<usize>,
+    /// Threads used to walk and size (1 = sequential; unset = one per CPU)
+    pub jobs: Option<usize>,
+    /// Skip folders smaller than this (e.g. "10MB", "500K")
+    pub min_size: Option<String>,
+    /// Traverse symlinked directories when scanning and sizing
+    pub follow_symlinks: bool,
+    /// Skip directories ignored by `.gitignore` files (`.claude` is always kept)
+    pub respect_gitignore: bool,
+    /// Reuse sizes of folders unmodified since the last run
+    pub cache_sizes: bool,
+    /// Count allocated disk blocks instead of file lengths (Unix)
+    pub disk_usage: bool,
+    /// Ask once before the first TUI scan of the whole home directory
+    pub confirm_home_scan: bool,
+}
+
+/// Project type detection configuration
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// Marker file -> type label, checked in order before built-in detection
+    pub custom_markers: Vec<(String, String)>,
+    /// Directories searched for markers, from the parent of `.claude` upwards
+    pub max_ancestors: usize,
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        Self {
+            custom_markers: Vec::new(),
+            max_ancestors: crate::project::DEFAULT_MAX_ANCESTORS,
+        }
+    }
+}
+
+/// Deletion safety configuration
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SafetyConfig {
+    /// Folders that must never be deleted (anything at or under these paths)
+    pub protected_paths: Vec<PathBuf>,
+    /// Ask again before deleting folders modified within this many days
+    /// (0 = off); `--delete-all` skips them
+    pub protect_recent_days: u64,
+}
+
+impl SafetyConfig {
+    /// `protected_paths` with `~` expanded
+    pub fn expanded_protected_paths(&self) -> Vec<PathBuf> {
+        self.protected_paths
+            .iter()
+            .map(|p| crate::utils::expand_tilde(p))
+            .collect()
+    }
+
+    /// How recently a folder must have changed to count as in use
+    pub fn recent_window(&self) -> Option<Duration> {
+        (self.protect_recent_days > 0)
+            .then(|| Duration::from_secs(self.protect_recent_days * 24 * 60 * 60))
+    }
+}
+
+/// Report-related configuration
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    /// Ages the report's age breakdown is split at (e.g. "90d", "6mo", "1y")
+    pub age_buckets: Vec<String>,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            age_buckets: vec!["1w".to_string(), "1mo".to_string(), "3mo".to_string()],
+        }
+    }
+}
+
+impl ReportConfig {
+    /// `age_buckets` parsed into durations
+    pub fn age_bucket_edges(&self) -> anyhow::Result<Vec<Duration>> {
+        self.age_buckets
+            .iter()
+            .map(|s| crate::utils::parse_duration(s))
+            .collect()
+    }
+}
+
+/// TUI colors by role: names ("cyan", "dark gray"), hex ("#005f87") or
+/// 256-color indexes ("244"). Unset or invalid entries use the defaults
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub header: Option<String>,
+    pub cursor: Option<String>,
+    pub selected: Option<String>,
+    pub arrival: Option<String>,
+    pub global_warning: Option<String>,
+    pub size_small: Option<String>,
+    pub size_medium: Option<String>,
+    pub size_large: Option<String>,
+    pub muted: Option<String>,
+    pub input: Option<String>,
+    pub danger: Option<String>,
+    pub safe: Option<String>,
+    pub status_scanning: Option<String>,
+    pub status_browsing: Option<String>,
+    pub status_confirming: Option<String>,
+    pub status_deleting: Option<String>,
+    pub dialog: Option<String>,
+    pub dialog_bg: Option<String>,
 }
 
 /// Display-related configuration
@@ -35,8 +156,44 @@ pub struct DisplayConfig {
     pub show_project_type: bool,
     /// Show filter bar by default
     pub show_filter_bar: bool,
-    /// Default sort order: size_desc, size_asc, name_asc, name_desc, date_desc, date_asc
+    /// Default sort order: size_desc, size_asc, name_asc, name_desc, date_desc, date_asc,
+    /// disk_size_desc, file_count_desc
     pub default_sort: String,
+    /// Show on-disk size alongside apparent size when they differ
+    pub show_disk_size: bool,
+    /// Show the modification date (YYYY-MM-DD) instead of a relative age
+    pub show_date_column: bool,
+    /// Show folder count and total size in the terminal window title
+    pub window_title: bool,
+    /// Fuzzy (subsequence) search instead of substring search
+    pub fuzzy_search: bool,
+    /// Reopen the TUI with the sort order and filter bar left last time
+    pub remember_state: bool,
+    /// Color the size column by how big each folder is
+    pub size_colors: bool,
+    /// Size from which the size column uses the medium color
+    pub size_medium: String,
+    /// Size from which the size column uses the large color
+    pub size_large: String,
+}
+
+impl DisplayConfig {
+    /// `size_medium` and `size_large` in bytes, or None with size colors off
+    pub fn size_color_thresholds(&self) -> anyhow::Result<Option<(u64, u64)>> {
+        if !self.size_colors {
+            return Ok(None);
+        }
+        let medium = crate::utils::parse_size(&self.size_medium)?;
+        let large = crate::utils::parse_size(&self.size_large)?;
+        if medium > large {
+            anyhow::bail!(
+                "size_medium ({}) is larger than size_large ({})",
+                self.size_medium,
+                self.size_large
+            );
+        }
+        Ok(Some((medium, large)))
+    }
 }
 
 /// Behavior-related configuration
@@ -47,6 +204,30 @@ pub struct BehaviorConfig {
     pub permanent_delete: bool,
     /// Show confirmation dialog before delete
     pub confirm_delete: bool,
+    /// Always confirm permanent deletes, even when `confirm_delete` is off
+    pub confirm_permanent: bool,
+    /// Deletion history entries to keep (0 = unlimited)
+    pub max_history_entries: usize,
+    /// Seconds before `y` is accepted for a permanent delete (0 = no pause)
+    pub permanent_confirm_delay: u64,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            default_paths: Vec::new(),
+            exclude_patterns: Vec::new(),
+            include_global: false,
+            max_depth: None,
+            jobs: None,
+            min_size: None,
+            follow_symlinks: false,
+            respect_gitignore: false,
+            cache_sizes: false,
+            disk_usage: false,
+            confirm_home_scan: true,
+        }
+    }
 }
 
 impl Default for DisplayConfig {
@@ -55,6 +236,14 @@ impl Default for DisplayConfig {
             show_project_type: true,
             show_filter_bar: false,
             default_sort: "size_desc".to_string(),
+            show_disk_size: false,
+            show_date_column: false,
+            window_title: false,
+            fuzzy_search: false,
+            remember_state: false,
+            size_colors: true,
+            size_medium: "10MB".to_string(),
+            size_large: "100MB".to_string(),
         }
     }
 }
@@ -64,20 +253,21 @@ impl Default for BehaviorConfig {
         Self {
             permanent_delete: false,
             confirm_delete: true,
+            confirm_permanent: true,
+            max_history_entries: crate::history::DEFAULT_MAX_HISTORY_ENTRIES,
+            permanent_confirm_delay: 2,
         }
     }
 }
 
 impl Config {
-    /// Load configuration from file, using defaults if not found
-    pub fn load() -> Result<Self> {
-        let path = Self::config_path();
-
+    /// Load configuration from `path`, using defaults if not found
+    pub fn load_from(path: &Path) -> Result<Self> {
         if !path.exists() {
             return Ok(Self::default());
         }
 
-        let content = fs::read_to_string(&path)
+        let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config: {}", path.display()))?;
 
         toml::from_str(&content)
@@ -95,10 +285,8 @@ impl Config {
             })
     }
 
-    /// Create default config file if it doesn't exist
-    pub fn create_default_if_missing() -> Result<bool> {
-        let path = Self::config_path();
-
+    /// Create a default config file at `path` if it doesn't exist
+    pub fn create_default_if_missing(path: &Path) -> Result<bool> {
         if path.exists() {
             return Ok(false);
         }
@@ -108,15 +296,15 @@ impl Config {
             fs::create_dir_all(parent)?;
         }
 
-        let content = Self::default_config_content(&path);
-        fs::write(&path, content)?;
+        let content = Self::default_config_content(path);
+        fs::write(path, content)?;
         Ok(true)
     }
 
     /// Generate default config file content with comments
     fn default_config_content(path: &Path) -> String {
         format!(
-            r#"# ClaudeKill Configuration
+            r##"# ClaudeKill Configuration
 # Location: {}
 # Documentation: https://github.com/olbboy/claudekill#configuration
 
@@ -124,12 +312,42 @@ impl Config {
 # Default paths to scan (empty = home directory)
 # default_paths = ["~/Projects", "~/Work"]
 
-# Patterns to exclude from scanning
-# exclude_patterns = ["node_modules", ".git"]
+# Patterns to exclude from scanning. Globs (containing * ? [ {{) match the
+# full path, at any depth unless they start with / ("node_modules/**",
+# "*.bak"); other patterns exclude any path containing them.
+# exclude_patterns = ["node_modules/**", "archive"]
 
 # Include global ~/.claude folder in scan
 include_global = false
 
+# Maximum directory depth to descend (unset = unlimited, 0 = root only)
+# max_depth = 6
+
+# Threads used to walk and size folders (unset = one per CPU). 1 walks
+# sequentially, which is kinder to spinning disks
+# jobs = 4
+
+# Skip folders smaller than this size (e.g. "10MB", "500K")
+# min_size = "1MB"
+
+# Follow symlinked directories (may double-count shared data)
+follow_symlinks = false
+
+# Don't descend into directories ignored by .gitignore (build output etc.)
+respect_gitignore = false
+
+# Reuse sizes of .claude folders where nothing inside has changed since the
+# last run (checked by the newest modification time of any entry)
+cache_sizes = false
+
+# Count allocated disk blocks instead of file lengths, so sizes and the
+# reclaimed space match what `df` shows (Unix; --apparent-size overrides)
+disk_usage = false
+
+# With no --path and no default_paths the whole home directory is scanned;
+# ask once before the first such scan
+confirm_home_scan = true
+
 [display]
 # Show project type column
 show_project_type = true
@@ -137,30 +355,116 @@ show_project_type = true
 # Show filter bar by default
 show_filter_bar = false
 
-# Default sort: "size_desc", "size_asc", "name_asc", "name_desc", "date_desc", "date_asc"
+# Default sort: "size_desc", "size_asc", "name_asc", "name_desc", "date_desc", "date_asc",
+# "disk_size_desc", "file_count_desc"
 default_sort = "size_desc"
 
+# Show on-disk size next to apparent size when they differ (sparse/compressed files)
+show_disk_size = false
+
+# Show the modification date (YYYY-MM-DD) instead of the relative age (e.g. 3mo)
+show_date_column = false
+
+# Show folder count and total size in the terminal window title
+# (some terminals mishandle title escape sequences)
+window_title = false
+
+# Reopen with the sort order and filter bar visibility left at the last quit
+# (overrides default_sort and show_filter_bar)
+remember_state = false
+
+# Match search queries fzf-style (toggle in the TUI with z)
+fuzzy_search = false
+
+# Color the size column: small below size_medium, large from size_large up
+# (colors are the size_* roles under [theme])
+size_colors = true
+size_medium = "10MB"
+size_large = "100MB"
+
 [behavior]
 # Use permanent delete instead of moving to trash
 permanent_delete = false
 
 # Show confirmation dialog before deleting
 confirm_delete = true
-"#,
+
+# Always confirm permanent deletes, even when confirm_delete = false
+confirm_permanent = true
+
+# Deletion history entries to keep, oldest dropped first (0 = unlimited)
+max_history_entries = 100
+
+# Seconds the confirm dialog waits before accepting y for a permanent delete
+# (0 = no pause). Trash and quarantine deletes are never delayed.
+permanent_confirm_delay = 2
+
+[project]
+# Custom project types: [marker file, label] pairs relative to the project dir.
+# Checked in order before the built-in detection; the first match wins.
+# custom_markers = [[".meta/service.yaml", "Service"]]
+
+# Directories searched for markers, starting with the parent of .claude, so a
+# .claude nested in e.g. config/ gets its project's type (never above the scan root)
+max_ancestors = 3
+
+[safety]
+# Never delete .claude folders at or under these paths (~ is expanded)
+# protected_paths = ["~/Work/important-client"]
+
+# Folders modified within this many days probably belong to an active session:
+# they are marked in the list, need an extra confirmation and are skipped by
+# --delete-all (0 = off)
+protect_recent_days = 0
+
+[report]
+# Ages the --report age breakdown is split at (h, d, w, mo, y); --age-buckets
+# overrides this for one run
+age_buckets = ["1w", "1mo", "3mo"]
+
+[theme]
+# TUI colors: names ("cyan", "light blue", "dark gray"), hex ("#005f87") or
+# 256-color indexes ("244"). Unset or invalid entries keep the default.
+# header = "cyan"             # app name
+# cursor = "dark gray"        # highlighted row background
+# selected = "cyan"           # folders marked for deletion
+# arrival = "light yellow"    # large folders just found while scanning
+# global_warning = "red"      # the global ~/.claude folder
+# size_small = "green"        # size column below [display] size_medium
+# size_medium = "yellow"
+# size_large = "red"          # size column from [display] size_large up
+# muted = "dark gray"         # hints
+# input = "yellow"            # prompts and mode indicators
+# danger = "red"              # permanent delete mode
+# safe = "green"              # trash/quarantine mode
+# status_scanning = "yellow"
+# status_browsing = "green"
+# status_confirming = "magenta"
+# status_deleting = "red"
+# dialog = "yellow"           # confirm dialog text
+# dialog_bg = "black"         # help/confirm dialog background
+
+[keybinds]
+# Rebind normal-mode actions to space-separated keys; a rebound action loses
+# its default keys. Keys: a letter ("x", "G"), "space", "enter", "esc", "tab",
+# "up", "down", "pageup", "pagedown", "home", "end", "delete", "f1".."f12",
+# optionally prefixed with "ctrl+" or "alt+". Ctrl+C always quits
+# (stopping a running deletion first).
+# Actions: quit, up, down, page_up, page_down, top, bottom, next_match,
+# prev_match, toggle, select_all,
+# select_none, select_none_visible, invert_selection, open_folder, copy_path,
+# delete, delete_highlighted, toggle_permanent, help, details, search,
+# filter_bar, sort, min_size, type_filter, fuzzy, clear_filters, rescan
+# delete = "x"
+# down = "down j ctrl+n"
+"##,
             path.display()
         )
     }
 
     /// Parse sort order string to SortOrder enum
     pub fn parse_sort_order(&self) -> crate::filter::SortOrder {
-        match self.display.default_sort.as_str() {
-            "size_asc" => crate::filter::SortOrder::SizeAsc,
-            "name_asc" => crate::filter::SortOrder::NameAsc,
-            "name_desc" => crate::filter::SortOrder::NameDesc,
-            "date_desc" => crate::filter::SortOrder::DateDesc,
-            "date_asc" => crate::filter::SortOrder::DateAsc,
-            _ => crate::filter::SortOrder::SizeDesc, // Default
-        }
+        self.display.default_sort.parse().unwrap_or_default()
     }
 }
 
@@ -172,9 +476,12 @@ mod tests {
     fn test_default_config() {
         let config = Config::default();
         assert!(!config.scan.include_global);
+        assert!(config.scan.confirm_home_scan);
         assert!(!config.behavior.permanent_delete);
         assert!(config.behavior.confirm_delete);
+        assert!(config.behavior.confirm_permanent);
         assert!(config.display.show_project_type);
+        assert!(!config.display.remember_state);
     }
 
     #[test]
@@ -183,15 +490,32 @@ mod tests {
             [scan]
             include_global = true
             exclude_patterns = ["node_modules", "target"]
+            max_depth = 4
 
             [behavior]
             permanent_delete = true
+
+            [project]
+            custom_markers = [[".meta/service.yaml", "Service"]]
+
+            [safety]
+            protected_paths = ["~/Work/client"]
         "#;
 
         let config: Config = toml::from_str(toml).unwrap();
         assert!(config.scan.include_global);
         assert!(config.behavior.permanent_delete);
         assert_eq!(config.scan.exclude_patterns.len(), 2);
+        assert_eq!(config.scan.max_depth, Some(4));
+        assert_eq!(
+            config.project.custom_markers,
+            vec![(".meta/service.yaml".to_string(), "Service".to_string())]
+        );
+        assert_eq!(
+            config.safety.protected_paths,
+            vec![PathBuf::from("~/Work/client")]
+        );
+        assert_eq!(config.safety.recent_window(), None);
     }
 
     #[test]
@@ -211,6 +535,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_init_and_load_custom_path() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("dotfiles/claudekill.toml");
+
+        // Missing files load as defaults
+        assert!(Config::load_from(&path).unwrap().behavior.confirm_delete);
+
+        assert!(Config::create_default_if_missing(&path).unwrap());
+        assert!(!Config::create_default_if_missing(&path).unwrap());
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains(&path.display().to_string()));
+
+        fs::write(&path, "[behavior]\nconfirm_delete = false\n").unwrap();
+        assert!(!Config::load_from(&path).unwrap().behavior.confirm_delete);
+    }
+
     #[test]
     fn test_config_path_not_empty() {
         let path = Config::config_path();
@@ -0,0 +1,245 @@
+//! Compare a scan against a snapshot saved by an earlier export
+
+use crate::scanner::ClaudeFolder;
+use crate::utils::format_size;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The fields of an exported folder a diff needs
+#[derive(Debug, Deserialize)]
+struct SnapshotFolder {
+    path: String,
+    size: u64,
+}
+
+/// Just enough of a `--report --export json` file to find its folders
+#[derive(Debug, Deserialize)]
+struct ReportSnapshot {
+    all_folders: Vec<SnapshotFolder>,
+}
+
+/// Load folder sizes by path from a `--report --export json` file, a
+/// `--dry-run --json` array or `--stream` JSON Lines
+pub fn load_snapshot(path: &Path) -> Result<BTreeMap<String, u64>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read snapshot: {}", path.display()))?;
+
+    let folders: Vec<SnapshotFolder> =
+        if let Ok(report) = serde_json::from_str::<ReportSnapshot>(&content) {
+            report.all_folders
+        } else if let Ok(folders) = serde_json::from_str(&content) {
+            folders
+        } else {
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<_, _>>()
+                .with_context(|| {
+                    format!(
+                        "{} is not a JSON report, a --dry-run --json list or --stream output",
+                        path.display()
+                    )
+                })?
+        };
+
+    Ok(folders.into_iter().map(|f| (f.path, f.size)).collect())
+}
+
+/// Folders that appeared, disappeared or changed size between two scans,
+/// each sorted by path
+#[derive(Debug, Default, PartialEq)]
+pub struct ScanDiff {
+    /// (path, size now)
+    pub added: Vec<(String, u64)>,
+    /// (path, size before)
+    pub removed: Vec<(String, u64)>,
+    /// (path, size before, size now)
+    pub grown: Vec<(String, u64, u64)>,
+    /// (path, size before, size now)
+    pub shrunk: Vec<(String, u64, u64)>,
+}
+
+impl ScanDiff {
+    pub fn compare(before: &BTreeMap<String, u64>, now: &[ClaudeFolder]) -> Self {
+        let now: BTreeMap<String, u64> = now
+            .iter()
+            .map(|f| (f.path.to_string_lossy().to_string(), f.size))
+            .collect();
+
+        let mut diff = Self::default();
+        for (path, &size) in &now {
+            match before.get(path) {
+                None => diff.added.push((path.clone(), size)),
+                Some(&old) if size > old => diff.grown.push((path.clone(), old, size)),
+                Some(&old) if size < old => diff.shrunk.push((path.clone(), old, size)),
+                Some(_) => {}
+            }
+        }
+        for (path, &size) in before {
+            if !now.contains_key(path) {
+                diff.removed.push((path.clone(), size));
+            }
+        }
+        diff
+    }
+
+    /// Total size change in bytes (negative when space was freed)
+    pub fn net_change(&self) -> i128 {
+        let added: i128 = self.added.iter().map(|(_, s)| *s as i128).sum();
+        let removed: i128 = self.removed.iter().map(|(_, s)| *s as i128).sum();
+        let changed: i128 = self
+            .grown
+            .iter()
+            .chain(&self.shrunk)
+            .map(|(_, old, new)| *new as i128 - *old as i128)
+            .sum();
+        added - removed + changed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.grown.is_empty()
+            && self.shrunk.is_empty()
+    }
+
+    /// Print the added, removed, grown and shrunk sections to stdout
+    pub fn print(&self) {
+        if self.is_empty() {
+            println!("No changes.");
+            return;
+        }
+
+        let changed = |(p, old, new): &'_ (String, u64, u64)| {
+            let delta = format_delta(*new as i128 - *old as i128);
+            (p.clone(), delta, Some((*old, *new)))
+        };
+        print_section(
+            "Added",
+            self.added
+                .iter()
+                .map(|(p, s)| (p.clone(), format!("+{}", format_size(*s)), None)),
+        );
+        print_section(
+            "Removed",
+            self.removed
+                .iter()
+                .map(|(p, s)| (p.clone(), format!("-{}", format_size(*s)), None)),
+        );
+        print_section("Grown", self.grown.iter().map(changed));
+        print_section("Shrunk", self.shrunk.iter().map(changed));
+
+        println!("Net change: {}", format_delta(self.net_change()));
+    }
+}
+
+/// One section of the diff: a heading with a count, then a line per folder
+fn print_section(
+    title: &str,
+    rows: impl ExactSizeIterator<Item = (String, String, Option<(u64, u64)>)>,
+) {
+    if rows.len() == 0 {
+        return;
+    }
+    println!("{} ({}):", title, rows.len());
+    println!("{:-<60}", "");
+    for (path, delta, sizes) in rows {
+        match sizes {
+            Some((old, new)) => println!(
+                "  {:>11}  {}  ({} -> {})",
+                delta,
+                path,
+                format_size(old),
+                format_size(new)
+            ),
+            None => println!("  {:>11}  {}", delta, path),
+        }
+    }
+    println!();
+}
+
+/// A signed byte count, e.g. `+1.5 MB` or `-300 B`
+fn format_delta(delta: i128) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!(
+        "{}{}",
+        sign,
+        format_size(delta.unsigned_abs().min(u64::MAX as u128) as u64)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn folder(path: &str, size: u64) -> ClaudeFolder {
+        ClaudeFolder {
+            path: PathBuf::from(path),
+            size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compare_scans() {
+        let before: BTreeMap<String, u64> = [
+            ("/gone/.claude", 100),
+            ("/grew/.claude", 100),
+            ("/shrank/.claude", 100),
+            ("/same/.claude", 100),
+        ]
+        .into_iter()
+        .map(|(p, s)| (p.to_string(), s))
+        .collect();
+        let now = [
+            folder("/grew/.claude", 250),
+            folder("/shrank/.claude", 40),
+            folder("/same/.claude", 100),
+            folder("/new/.claude", 7),
+        ];
+
+        let diff = ScanDiff::compare(&before, &now);
+        assert_eq!(diff.added, [("/new/.claude".to_string(), 7)]);
+        assert_eq!(diff.removed, [("/gone/.claude".to_string(), 100)]);
+        assert_eq!(diff.grown, [("/grew/.claude".to_string(), 100, 250)]);
+        assert_eq!(diff.shrunk, [("/shrank/.claude".to_string(), 100, 40)]);
+        assert_eq!(diff.net_change(), 7 - 100 + 150 - 60);
+        assert_eq!(format_delta(-300), "-300 B");
+    }
+
+    #[test]
+    fn test_load_snapshot_formats() {
+        let temp = tempfile::tempdir().unwrap();
+        let folders = [folder("/a/.claude", 10), folder("/b/.claude", 20)];
+        let expected: BTreeMap<String, u64> = [
+            ("/a/.claude".to_string(), 10),
+            ("/b/.claude".to_string(), 20),
+        ]
+        .into_iter()
+        .collect();
+
+        let report =
+            crate::report::SpaceReport::generate(&folders, &crate::report::DEFAULT_AGE_BUCKETS);
+        let jsonl: String = folders
+            .iter()
+            .map(|f| serde_json::to_string(&crate::report::FolderRecord::from(f)).unwrap() + "\n")
+            .collect();
+        for (name, content) in [
+            ("report.json", report.to_json()),
+            ("dry-run.json", crate::report::folders_to_json(&folders)),
+            ("stream.jsonl", jsonl),
+        ] {
+            let path = temp.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            assert_eq!(load_snapshot(&path).unwrap(), expected, "{}", name);
+        }
+
+        let bogus = temp.path().join("bogus.txt");
+        std::fs::write(&bogus, "not json").unwrap();
+        assert!(load_snapshot(&bogus).is_err());
+    }
+}
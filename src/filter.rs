@@ -1,19 +1,34 @@
 //! Filtering and search functionality for folder lists
 
 use crate::scanner::ClaudeFolder;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use regex::{Regex, RegexBuilder};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
 use std::time::{Duration, SystemTime};
 
 /// Filter criteria for folders
 #[derive(Default, Clone)]
 pub struct Filter {
-    /// Text search in path
-    pub search_query: Option<String>,
+    /// Text search in path (a leading `/` makes it a regex); set through
+    /// `set_search` so the compiled regex stays in sync
+    search_query: Option<String>,
+    /// Compiled form of a regex `search_query` (None if it failed to compile)
+    search_regex: Option<Regex>,
+    /// Match plain queries as fzf-style subsequences instead of substrings
+    pub fuzzy: bool,
     /// Filter by project types (empty = all)
     pub project_types: Vec<String>,
     /// Minimum size in bytes
     pub min_size: Option<u64>,
     /// Maximum age (folders older than this pass)
     pub max_age: Option<Duration>,
+    /// Let folders with an unknown modification time pass the age filter
+    pub include_unknown_age: bool,
 }
 
 impl Filter {
@@ -21,8 +36,17 @@ impl Filter {
     pub fn matches(&self, folder: &ClaudeFolder) -> bool {
         // Search query filter (case-insensitive path match)
         if let Some(ref query) = self.search_query {
-            let path_str = folder.path.to_string_lossy().to_lowercase();
-            if !path_str.contains(&query.to_lowercase()) {
+            let path_str = folder.path.to_string_lossy();
+            let matched = match (&self.search_regex, regex_pattern(query)) {
+                (Some(regex), _) => regex.is_match(&path_str),
+                (None, None) if self.fuzzy => {
+                    fuzzy_matcher().fuzzy_match(&path_str, query).is_some()
+                }
+                // Invalid regex: fall back to a plain substring match
+                (None, Some(pattern)) => path_str.to_lowercase().contains(&pattern.to_lowercase()),
+                (None, None) => path_str.to_lowercase().contains(&query.to_lowercase()),
+            };
+            if !matched {
                 return false;
             }
         }
@@ -41,18 +65,72 @@ impl Filter {
 
         // Age filter (folders older than max_age pass)
         if let Some(max_age) = self.max_age {
-            if let Some(modified) = folder.modified_at {
-                if let Ok(elapsed) = SystemTime::now().duration_since(modified) {
+            match folder.modified_at {
+                Some(modified) => {
+                    let elapsed = SystemTime::now()
+                        .duration_since(modified)
+                        .unwrap_or_default();
                     if elapsed < max_age {
                         return false;
                     }
                 }
+                None if !self.include_unknown_age => return false,
+                None => {}
             }
         }
 
         true
     }
 
+    /// Text search in path, if any
+    pub fn search_query(&self) -> Option<&str> {
+        self.search_query.as_deref()
+    }
+
+    /// Set the search query, compiling it if it's a `/regex`
+    pub fn set_search(&mut self, query: Option<String>) {
+        self.search_regex = query
+            .as_deref()
+            .and_then(regex_pattern)
+            .and_then(|pattern| {
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .ok()
+            });
+        self.search_query = query;
+    }
+
+    /// Fuzzy match score of a folder against the query (higher is better)
+    ///
+    /// `None` unless fuzzy search is on and a plain query is set.
+    pub fn fuzzy_score(&self, folder: &ClaudeFolder) -> Option<i64> {
+        let query = self.search_query.as_deref()?;
+        if !self.fuzzy || regex_pattern(query).is_some() {
+            return None;
+        }
+        fuzzy_matcher().fuzzy_match(&folder.path.to_string_lossy(), query)
+    }
+
+    /// Whether results should be ranked by fuzzy score
+    pub fn is_fuzzy_active(&self) -> bool {
+        self.fuzzy
+            && self
+                .search_query
+                .as_deref()
+                .is_some_and(|q| regex_pattern(q).is_none())
+    }
+
+    /// Whether the search query is a regex that failed to compile
+    pub fn invalid_regex(&self) -> bool {
+        self.search_regex.is_none()
+            && self
+                .search_query
+                .as_deref()
+                .and_then(regex_pattern)
+                .is_some()
+    }
+
     /// Check if any filter is active
     pub fn is_active(&self) -> bool {
         self.search_query.is_some()
@@ -61,12 +139,74 @@ impl Filter {
             || self.max_age.is_some()
     }
 
-    /// Clear all filters
+    /// Clear all filters (the fuzzy search setting is kept)
     pub fn clear(&mut self) {
-        *self = Self::default();
+        *self = Self {
+            fuzzy: self.fuzzy,
+            ..Self::default()
+        };
+    }
+
+    /// Filter that only lets folders older than `max_age` through
+    pub fn older_than(max_age: Option<Duration>, include_unknown_age: bool) -> Self {
+        Self {
+            max_age,
+            include_unknown_age,
+            ..Self::default()
+        }
     }
 }
 
+/// Shared fuzzy matcher (smart case, like fzf)
+fn fuzzy_matcher() -> &'static SkimMatcherV2 {
+    static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+    MATCHER.get_or_init(|| SkimMatcherV2::default().smart_case())
+}
+
+/// The pattern of a `/regex` search query
+fn regex_pattern(query: &str) -> Option<&str> {
+    query
+        .strip_prefix('/')
+        .filter(|pattern| !pattern.is_empty())
+}
+
+/// Find the most recently modified folder in each group of sibling projects
+///
+/// Folders are grouped by the directory containing their project (the
+/// grandparent of `.claude`). Only groups with more than one folder yield a
+/// result, since a lone folder gives no hint about which project is active.
+pub fn active_folder_paths(folders: &[ClaudeFolder]) -> HashSet<PathBuf> {
+    let mut groups: HashMap<&Path, Vec<&ClaudeFolder>> = HashMap::new();
+    for folder in folders {
+        if let Some(group) = folder.path.parent().and_then(Path::parent) {
+            groups.entry(group).or_default().push(folder);
+        }
+    }
+
+    groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .filter_map(|group| {
+            group
+                .into_iter()
+                .filter(|f| f.modified_at.is_some())
+                .max_by_key(|f| f.modified_at)
+                .map(|f| f.path.clone())
+        })
+        .collect()
+}
+
+/// Split `folders` into the ones `active_folder_paths` picks among `all`
+/// (every folder found, so filters don't change which sibling counts as
+/// active) and the rest
+pub fn partition_active(
+    folders: Vec<ClaudeFolder>,
+    all: &[ClaudeFolder],
+) -> (Vec<ClaudeFolder>, Vec<ClaudeFolder>) {
+    let active = active_folder_paths(all);
+    folders.into_iter().partition(|f| active.contains(&f.path))
+}
+
 /// Sort order for folder list
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum SortOrder {
@@ -77,6 +217,8 @@ pub enum SortOrder {
     NameDesc,
     DateDesc,
     DateAsc,
+    DiskSizeDesc,
+    FileCountDesc,
 }
 
 impl SortOrder {
@@ -88,7 +230,9 @@ impl SortOrder {
             Self::NameAsc => Self::NameDesc,
             Self::NameDesc => Self::DateDesc,
             Self::DateDesc => Self::DateAsc,
-            Self::DateAsc => Self::SizeDesc,
+            Self::DateAsc => Self::DiskSizeDesc,
+            Self::DiskSizeDesc => Self::FileCountDesc,
+            Self::FileCountDesc => Self::SizeDesc,
         }
     }
 
@@ -101,10 +245,87 @@ impl SortOrder {
             Self::NameDesc => "Name Z-A",
             Self::DateDesc => "Newest",
             Self::DateAsc => "Oldest",
+            Self::DiskSizeDesc => "Disk ↓",
+            Self::FileCountDesc => "Files ↓",
+        }
+    }
+
+    /// Name used in config and state files (e.g. "size_desc")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SizeDesc => "size_desc",
+            Self::SizeAsc => "size_asc",
+            Self::NameAsc => "name_asc",
+            Self::NameDesc => "name_desc",
+            Self::DateDesc => "date_desc",
+            Self::DateAsc => "date_asc",
+            Self::DiskSizeDesc => "disk_size_desc",
+            Self::FileCountDesc => "file_count_desc",
+        }
+    }
+
+    /// Parse a name written by `name`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut order = Self::default();
+        loop {
+            if order.name() == name {
+                return Some(order);
+            }
+            order = order.next();
+            if order == Self::default() {
+                return None;
+            }
+        }
+    }
+
+    /// Whether this order needs folder sizes (meaningless with `--no-size`)
+    pub fn uses_sizes(&self) -> bool {
+        matches!(
+            self,
+            Self::SizeDesc | Self::SizeAsc | Self::DiskSizeDesc | Self::FileCountDesc
+        )
+    }
+
+    /// How two folders compare in this order
+    pub fn compare(&self, a: &ClaudeFolder, b: &ClaudeFolder) -> Ordering {
+        match self {
+            Self::SizeDesc => b.size.cmp(&a.size),
+            Self::SizeAsc => a.size.cmp(&b.size),
+            Self::NameAsc => a.path.cmp(&b.path),
+            Self::NameDesc => b.path.cmp(&a.path),
+            Self::DateDesc => b.modified_at.cmp(&a.modified_at),
+            Self::DateAsc => a.modified_at.cmp(&b.modified_at),
+            Self::DiskSizeDesc => b.disk_size.cmp(&a.disk_size),
+            Self::FileCountDesc => b.file_count.cmp(&a.file_count),
+        }
+    }
+
+    /// Label to show when the list has a date column rather than ages
+    pub fn date_label(&self) -> &'static str {
+        match self {
+            Self::DateDesc => "Date ↓",
+            Self::DateAsc => "Date ↑",
+            _ => self.label(),
         }
     }
 }
 
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s.trim()).ok_or_else(|| {
+            let mut names = vec![Self::default().name()];
+            let mut order = Self::default().next();
+            while order != Self::default() {
+                names.push(order.name());
+                order = order.next();
+            }
+            format!("unknown sort order '{}': use {}", s, names.join(", "))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +338,7 @@ mod tests {
             project_type: project_type.to_string(),
             selected: false,
             modified_at: Some(SystemTime::now()),
+            ..Default::default()
         }
     }
 
@@ -129,12 +351,46 @@ mod tests {
         assert!(filter.matches(&folder));
 
         // Matching search
-        filter.search_query = Some("rust".to_string());
+        filter.set_search(Some("rust".to_string()));
         assert!(filter.matches(&folder));
 
         // Non-matching search
-        filter.search_query = Some("python".to_string());
+        filter.set_search(Some("python".to_string()));
+        assert!(!filter.matches(&folder));
+    }
+
+    #[test]
+    fn test_filter_regex_search() {
+        let folder = make_folder("/home/user/api-v2/.claude", 1000, "Rust");
+        let mut filter = Filter::default();
+
+        filter.set_search(Some(r"/API-v\d".to_string()));
+        assert!(filter.matches(&folder));
+        assert!(!filter.invalid_regex());
+
+        filter.set_search(Some(r"/web-v\d".to_string()));
+        assert!(!filter.matches(&folder));
+
+        // Invalid regex falls back to substring matching
+        filter.set_search(Some("/api-v2(".to_string()));
+        assert!(filter.invalid_regex());
         assert!(!filter.matches(&folder));
+        assert!(filter.matches(&make_folder("/x/api-v2(/.claude", 1, "Rust")));
+    }
+
+    #[test]
+    fn test_filter_fuzzy_search() {
+        let folder = make_folder("/home/user/claudekill/.claude", 1000, "Rust");
+        let mut filter = Filter::default();
+
+        filter.set_search(Some("cldkl".to_string()));
+        assert!(!filter.matches(&folder));
+        assert_eq!(filter.fuzzy_score(&folder), None);
+
+        filter.fuzzy = true;
+        assert!(filter.matches(&folder));
+        assert!(filter.fuzzy_score(&folder).is_some());
+        assert!(!filter.matches(&make_folder("/srv/web/.claude", 1, "Node")));
     }
 
     #[test]
@@ -171,28 +427,112 @@ mod tests {
         assert!(!filter.matches(&folder));
     }
 
+    #[test]
+    fn test_filter_max_age() {
+        let month = Duration::from_secs(30 * 24 * 60 * 60);
+        let fresh = make_folder("/fresh/.claude", 1, "Rust");
+        let mut stale = make_folder("/stale/.claude", 1, "Rust");
+        stale.modified_at = Some(SystemTime::now() - 2 * month);
+        let mut unknown = make_folder("/unknown/.claude", 1, "Rust");
+        unknown.modified_at = None;
+
+        let filter = Filter::older_than(Some(month), false);
+        assert!(!filter.matches(&fresh));
+        assert!(filter.matches(&stale));
+        assert!(!filter.matches(&unknown));
+
+        let filter = Filter::older_than(Some(month), true);
+        assert!(filter.matches(&unknown));
+    }
+
     #[test]
     fn test_filter_is_active() {
         let mut filter = Filter::default();
         assert!(!filter.is_active());
 
-        filter.search_query = Some("test".to_string());
+        filter.set_search(Some("test".to_string()));
         assert!(filter.is_active());
 
         filter.clear();
         assert!(!filter.is_active());
     }
 
+    #[test]
+    fn test_active_folder_paths_protects_newest_in_group() {
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut old = make_folder("/mono/packages/a/.claude", 100, "Node.js");
+        old.modified_at = Some(now - day * 10);
+        let mut newest = make_folder("/mono/packages/b/.claude", 100, "Node.js");
+        newest.modified_at = Some(now - day);
+        let mut older = make_folder("/mono/packages/c/.claude", 100, "Node.js");
+        older.modified_at = Some(now - day * 30);
+        let lone = make_folder("/other/app/.claude", 100, "Rust");
+
+        let active = active_folder_paths(&[old, newest, older, lone]);
+
+        assert_eq!(active.len(), 1);
+        assert!(active.contains(&PathBuf::from("/mono/packages/b/.claude")));
+    }
+
+    #[test]
+    fn test_partition_active_uses_every_folder_found() {
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut old = make_folder("/mono/packages/a/.claude", 100, "Node.js");
+        old.modified_at = Some(now - day * 10);
+        let mut newest = make_folder("/mono/packages/b/.claude", 100, "Node.js");
+        newest.modified_at = Some(now - day);
+        let all = vec![old.clone(), newest.clone()];
+
+        let (active, rest) = partition_active(all.clone(), &all);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].path, newest.path);
+        assert_eq!(rest.len(), 1);
+
+        // The newest sibling was filtered out, so the old one is fair game
+        let (active, rest) = partition_active(vec![old], &all);
+        assert!(active.is_empty());
+        assert_eq!(rest.len(), 1);
+    }
+
     #[test]
     fn test_sort_order_cycle() {
         let order = SortOrder::SizeDesc;
         assert_eq!(order.next(), SortOrder::SizeAsc);
         assert_eq!(order.next().next(), SortOrder::NameAsc);
+
+        // Every order survives a round trip through its name
+        let mut order = SortOrder::default();
+        let mut seen = Vec::new();
+        loop {
+            assert_eq!(SortOrder::from_name(order.name()), Some(order));
+            seen.push(order);
+            order = order.next();
+            if order == SortOrder::default() {
+                break;
+            }
+            assert!(
+                !seen.contains(&order),
+                "next() never returns to the default"
+            );
+        }
+        assert!(seen.contains(&SortOrder::FileCountDesc));
+        assert_eq!(SortOrder::from_name("bogus"), None);
+        assert!(SortOrder::FileCountDesc.uses_sizes());
+        assert!(!SortOrder::DateDesc.uses_sizes());
+        assert_eq!("name_desc".parse(), Ok(SortOrder::NameDesc));
+        assert!("bogus"
+            .parse::<SortOrder>()
+            .unwrap_err()
+            .contains("file_count_desc"));
     }
 
     #[test]
     fn test_sort_order_labels() {
         assert_eq!(SortOrder::SizeDesc.label(), "Size ↓");
         assert_eq!(SortOrder::NameAsc.label(), "Name A-Z");
+        assert_eq!(SortOrder::DateDesc.date_label(), "Date ↓");
+        assert_eq!(SortOrder::SizeDesc.date_label(), "Size ↓");
     }
 }
@@ -7,14 +7,17 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Maximum history entries to retain
-const MAX_HISTORY_ENTRIES: usize = 100;
+use crate::trash::TrashItemInfo;
+
+/// History entries retained unless `max_history_entries` says otherwise
+pub const DEFAULT_MAX_HISTORY_ENTRIES: usize = 100;
 
 /// Deletion method used
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DeletionMethod {
     Trash,
     Permanent,
+    Quarantine,
 }
 
 /// Record of a single deletion operation
@@ -24,6 +27,15 @@ pub struct DeletionRecord {
     pub paths: Vec<PathBuf>,
     pub total_size: u64,
     pub method: DeletionMethod,
+    /// Where each path was moved to (quarantine deletions only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quarantine_paths: Vec<PathBuf>,
+    /// Exact Trash entries created (trash deletions on Linux/Windows only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash_items: Vec<TrashItemInfo>,
+    /// `.tar.gz` written before each path was deleted (permanent with --backup)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backup_paths: Vec<PathBuf>,
 }
 
 impl DeletionRecord {
@@ -33,18 +45,71 @@ impl DeletionRecord {
             paths,
             total_size,
             method,
+            quarantine_paths: Vec::new(),
+            trash_items: Vec::new(),
+            backup_paths: Vec::new(),
         }
     }
 
     pub fn can_undo(&self) -> bool {
-        self.method == DeletionMethod::Trash
+        match self.method {
+            DeletionMethod::Trash => true,
+            DeletionMethod::Quarantine => !self.quarantine_paths.is_empty(),
+            // Only with a backup archive of every path still on disk
+            DeletionMethod::Permanent => {
+                !self.backup_paths.is_empty()
+                    && self.backup_paths.len() == self.paths.len()
+                    && self.backup_paths.iter().all(|p| p.is_file())
+            }
+        }
+    }
+}
+
+/// Aggregate totals over the recorded deletions
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HistoryStats {
+    pub operations: usize,
+    pub folders: usize,
+    pub total_size: u64,
+    pub trash_size: u64,
+    pub permanent_size: u64,
+    pub quarantine_size: u64,
+    /// Oldest and newest record
+    pub first: Option<DateTime<Utc>>,
+    pub last: Option<DateTime<Utc>>,
+}
+
+impl HistoryStats {
+    /// Average folders deleted per operation
+    pub fn average_folders(&self) -> f64 {
+        if self.operations == 0 {
+            0.0
+        } else {
+            self.folders as f64 / self.operations as f64
+        }
     }
 }
 
 /// Deletion history manager
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct History {
     pub records: Vec<DeletionRecord>,
+    /// Oldest records are dropped beyond this many (0 = unlimited)
+    #[serde(skip, default = "default_max_entries")]
+    max_entries: usize,
+}
+
+fn default_max_entries() -> usize {
+    DEFAULT_MAX_HISTORY_ENTRIES
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            records: Vec::new(),
+            max_entries: DEFAULT_MAX_HISTORY_ENTRIES,
+        }
+    }
 }
 
 impl History {
@@ -74,17 +139,87 @@ impl History {
         Ok(())
     }
 
+    /// Set the entry cap (0 = unlimited), trimming records already over it
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self.trim();
+        self
+    }
+
     /// Add a deletion record
     pub fn add(&mut self, record: DeletionRecord) {
         self.records.push(record);
+        self.trim();
+    }
 
-        // Trim to max entries
-        if self.records.len() > MAX_HISTORY_ENTRIES {
-            self.records
-                .drain(0..self.records.len() - MAX_HISTORY_ENTRIES);
+    /// Drop the oldest records beyond the entry cap
+    fn trim(&mut self) {
+        if self.max_entries > 0 && self.records.len() > self.max_entries {
+            self.records.drain(0..self.records.len() - self.max_entries);
         }
     }
 
+    /// Remove records (all, or only those older than `older_than`)
+    /// Returns how many were removed
+    pub fn clear(&mut self, older_than: Option<std::time::Duration>) -> usize {
+        let before = self.records.len();
+        match older_than.and_then(|age| chrono::Duration::from_std(age).ok()) {
+            Some(age) => {
+                let cutoff = Utc::now() - age;
+                self.records.retain(|r| r.timestamp >= cutoff);
+            }
+            None => self.records.clear(),
+        }
+        before - self.records.len()
+    }
+
+    /// Totals over every record
+    pub fn stats(&self) -> HistoryStats {
+        self.records
+            .iter()
+            .fold(HistoryStats::default(), |mut stats, record| {
+                stats.operations += 1;
+                stats.folders += record.paths.len();
+                stats.total_size += record.total_size;
+                match record.method {
+                    DeletionMethod::Trash => stats.trash_size += record.total_size,
+                    DeletionMethod::Permanent => stats.permanent_size += record.total_size,
+                    DeletionMethod::Quarantine => stats.quarantine_size += record.total_size,
+                }
+                let at = record.timestamp;
+                stats.first = Some(stats.first.map_or(at, |first| first.min(at)));
+                stats.last = Some(stats.last.map_or(at, |last| last.max(at)));
+                stats
+            })
+    }
+
+    /// Export records as JSON, in the same shape as the history file
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.records).unwrap_or_default()
+    }
+
+    /// Export records as CSV, one row per operation (paths joined by `;`)
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("Timestamp,Method,Folders,Size (bytes),Size (human),Paths\n");
+        for record in &self.records {
+            let paths: Vec<String> = record
+                .paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            csv.push_str(&format!(
+                "{},{:?},{},{},{},\"{}\"\n",
+                record.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                record.method,
+                record.paths.len(),
+                record.total_size,
+                crate::utils::format_size(record.total_size),
+                paths.join(";").replace('"', "\"\"")
+            ));
+        }
+        csv
+    }
+
     /// Get the last undoable deletion
     pub fn last_undoable(&self) -> Option<&DeletionRecord> {
         self.records.iter().rev().find(|r| r.can_undo())
@@ -92,11 +227,7 @@ impl History {
 
     /// Remove the last undoable record (after successful undo)
     pub fn remove_last_undoable(&mut self) {
-        if let Some(pos) = self
-            .records
-            .iter()
-            .rposition(|r| r.method == DeletionMethod::Trash)
-        {
+        if let Some(pos) = self.records.iter().rposition(|r| r.can_undo()) {
             self.records.remove(pos);
         }
     }
@@ -113,7 +244,7 @@ impl History {
     }
 }
 
-/// Attempt to undo the last trash-based deletion
+/// Attempt to undo the last trash, quarantine or backed-up permanent deletion
 /// Returns the list of paths that were successfully restored
 pub fn undo_last() -> Result<Option<Vec<PathBuf>>> {
     let mut history = History::load()?;
@@ -122,27 +253,79 @@ pub fn undo_last() -> Result<Option<Vec<PathBuf>>> {
         return Ok(None);
     };
 
-    if record.method != DeletionMethod::Trash {
-        anyhow::bail!("Last deletion was permanent and cannot be undone");
+    let restored = restore_record(&record);
+
+    // If at least one was restored, remove from history
+    if !restored.is_empty() {
+        history.remove_last_undoable();
+        history.save()?;
+    }
+
+    Ok(Some(restored))
+}
+
+/// Undo a specific history entry by its index in `History::records`
+/// Returns the list of paths that were successfully restored
+pub fn undo_record(index: usize) -> Result<Vec<PathBuf>> {
+    let mut history = History::load()?;
+
+    let Some(record) = history.records.get(index).cloned() else {
+        anyhow::bail!("No history entry #{}", index + 1);
+    };
+
+    if record.method == DeletionMethod::Permanent && record.backup_paths.is_empty() {
+        anyhow::bail!(
+            "History entry #{} was a permanent deletion without a backup and cannot be undone",
+            index + 1
+        );
+    }
+    if record.method == DeletionMethod::Permanent && !record.can_undo() {
+        anyhow::bail!(
+            "History entry #{} cannot be undone: its backup archives are missing",
+            index + 1
+        );
+    }
+    if !record.can_undo() {
+        anyhow::bail!("History entry #{} cannot be undone", index + 1);
+    }
+
+    let restored = restore_record(&record);
+
+    // If at least one was restored, remove just this record
+    if !restored.is_empty() {
+        history.records.remove(index);
+        history.save()?;
     }
 
-    // Attempt to restore from trash
+    Ok(restored)
+}
+
+/// Restore every path of a record, reporting failures
+fn restore_record(record: &DeletionRecord) -> Vec<PathBuf> {
     let mut restored = Vec::new();
     let mut errors = Vec::new();
 
-    for path in &record.paths {
-        match restore_from_trash(path) {
+    for (i, path) in record.paths.iter().enumerate() {
+        let result = match record.method {
+            DeletionMethod::Quarantine => match record.quarantine_paths.get(i) {
+                Some(quarantined) => crate::trash::restore_from_quarantine(quarantined, path),
+                None => Err(anyhow::anyhow!("No quarantine location recorded")),
+            },
+            DeletionMethod::Permanent => match record.backup_paths.get(i) {
+                Some(archive) => crate::trash::restore_from_backup(archive, path),
+                None => Err(anyhow::anyhow!("No backup archive recorded")),
+            },
+            _ => match record.trash_items.iter().find(|t| &t.original_path == path) {
+                Some(item) => crate::trash::restore_trash_item(item),
+                None => restore_from_trash(path),
+            },
+        };
+        match result {
             Ok(()) => restored.push(path.clone()),
             Err(e) => errors.push(format!("{}: {}", path.display(), e)),
         }
     }
 
-    // If at least one was restored, remove from history
-    if !restored.is_empty() {
-        history.remove_last_undoable();
-        history.save()?;
-    }
-
     if !errors.is_empty() {
         eprintln!("Some folders could not be restored:");
         for err in errors {
@@ -150,7 +333,7 @@ pub fn undo_last() -> Result<Option<Vec<PathBuf>>> {
         }
     }
 
-    Ok(Some(restored))
+    restored
 }
 
 /// Restore a path from system trash (platform-specific)
@@ -205,9 +388,23 @@ fn restore_from_trash(path: &Path) -> Result<()> {
 
     #[cfg(target_os = "windows")]
     {
-        // Windows requires complex COM interfaces for trash restoration
+        use trash::os_limited;
+
+        // Restore the most recently deleted Recycle Bin item for this path
+        let newest = os_limited::list()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|item| item.original_path() == path)
+            .max_by_key(|item| item.time_deleted);
+
+        if let Some(item) = newest {
+            return os_limited::restore_all([item])
+                .with_context(|| format!("Failed to restore: {}", path.display()));
+        }
+
+        // Fallback: inform user
         anyhow::bail!(
-            "Auto-restore not supported on Windows. Please restore manually from Recycle Bin: {}",
+            "Could not auto-restore. Please restore manually from Recycle Bin: {}",
             path.display()
         );
     }
@@ -237,6 +434,42 @@ mod tests {
             DeletionMethod::Permanent,
         );
         assert!(!permanent_record.can_undo());
+
+        // Permanent deletions are undoable while their backups exist
+        let temp = tempfile::tempdir().unwrap();
+        let archive = temp.path().join("backup.tar.gz");
+        let mut backed_up = permanent_record.clone();
+        backed_up.backup_paths = vec![archive.clone()];
+        assert!(!backed_up.can_undo());
+        std::fs::write(&archive, "").unwrap();
+        assert!(backed_up.can_undo());
+
+        let mut quarantine_record = DeletionRecord::new(
+            vec![PathBuf::from("/test/.claude")],
+            1024,
+            DeletionMethod::Quarantine,
+        );
+        assert!(!quarantine_record.can_undo());
+        quarantine_record.quarantine_paths = vec![PathBuf::from("/q/test/.claude")];
+        assert!(quarantine_record.can_undo());
+    }
+
+    #[test]
+    fn test_record_trash_items_roundtrip() {
+        // Records written before trash_items existed still load
+        let old = r#"{"timestamp":"2024-01-01T00:00:00Z","paths":["/a/.claude"],"total_size":1,"method":"Trash"}"#;
+        let record: DeletionRecord = serde_json::from_str(old).unwrap();
+        assert!(record.trash_items.is_empty());
+
+        let mut record = record;
+        record.trash_items = vec![TrashItemInfo {
+            id: "/trash/info/.claude.2.trashinfo".into(),
+            original_path: PathBuf::from("/a/.claude"),
+            time_deleted: 1_700_000_000,
+        }];
+        let json = serde_json::to_string(&record).unwrap();
+        let loaded: DeletionRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.trash_items, record.trash_items);
     }
 
     #[test]
@@ -247,6 +480,33 @@ mod tests {
         assert_eq!(history.records.len(), 1);
     }
 
+    #[test]
+    fn test_history_max_entries() {
+        let mut history = History::default();
+        for size in 0..(DEFAULT_MAX_HISTORY_ENTRIES as u64 + 5) {
+            history.add(DeletionRecord::new(vec![], size, DeletionMethod::Trash));
+        }
+        assert_eq!(history.records.len(), DEFAULT_MAX_HISTORY_ENTRIES);
+        assert_eq!(history.records[0].total_size, 5);
+
+        // A smaller cap on a later run trims the oldest records on load
+        let json = serde_json::to_string(&history).unwrap();
+        let loaded: History = serde_json::from_str(&json).unwrap();
+        let mut history = loaded.with_max_entries(3);
+        assert_eq!(history.records.len(), 3);
+        assert_eq!(history.records[0].total_size, 102);
+
+        // 0 means unlimited
+        let mut unlimited = History::default().with_max_entries(0);
+        for _ in 0..(DEFAULT_MAX_HISTORY_ENTRIES + 5) {
+            unlimited.add(DeletionRecord::new(vec![], 0, DeletionMethod::Trash));
+        }
+        assert_eq!(unlimited.records.len(), DEFAULT_MAX_HISTORY_ENTRIES + 5);
+
+        history.add(DeletionRecord::new(vec![], 999, DeletionMethod::Trash));
+        assert_eq!(history.records.len(), 3);
+    }
+
     #[test]
     fn test_history_last_undoable() {
         let mut history = History::default();
@@ -274,6 +534,86 @@ mod tests {
         assert!(history.records.iter().all(|r| r.total_size != 300));
     }
 
+    #[test]
+    fn test_history_clear() {
+        let mut history = History::default();
+        let mut old = DeletionRecord::new(vec![], 100, DeletionMethod::Trash);
+        old.timestamp = Utc::now() - chrono::Duration::days(60);
+        history.add(old);
+        history.add(DeletionRecord::new(vec![], 200, DeletionMethod::Trash));
+
+        let removed = history.clear(Some(std::time::Duration::from_secs(30 * 24 * 60 * 60)));
+        assert_eq!(removed, 1);
+        assert_eq!(history.records.len(), 1);
+        assert_eq!(history.records[0].total_size, 200);
+
+        assert_eq!(history.clear(None), 1);
+        assert!(history.records.is_empty());
+    }
+
+    #[test]
+    fn test_history_stats() {
+        let mut history = History::default();
+        assert_eq!(history.stats(), HistoryStats::default());
+        assert_eq!(history.stats().average_folders(), 0.0);
+
+        let mut old = DeletionRecord::new(
+            vec![PathBuf::from("/a/.claude"), PathBuf::from("/b/.claude")],
+            100,
+            DeletionMethod::Trash,
+        );
+        old.timestamp = Utc::now() - chrono::Duration::days(10);
+        let first = old.timestamp;
+        history.add(old);
+        history.add(DeletionRecord::new(
+            vec![PathBuf::from("/c/.claude")],
+            300,
+            DeletionMethod::Permanent,
+        ));
+        history.add(DeletionRecord::new(vec![], 50, DeletionMethod::Trash));
+
+        let stats = history.stats();
+        assert_eq!(stats.operations, 3);
+        assert_eq!(stats.folders, 3);
+        assert_eq!(stats.total_size, 450);
+        assert_eq!(stats.trash_size, 150);
+        assert_eq!(stats.permanent_size, 300);
+        assert_eq!(stats.quarantine_size, 0);
+        assert_eq!(stats.average_folders(), 1.0);
+        assert_eq!(stats.first, Some(first));
+        assert_eq!(stats.last, Some(history.records[2].timestamp));
+    }
+
+    #[test]
+    fn test_history_export() {
+        let mut history = History::default();
+        let mut record = DeletionRecord::new(
+            vec![
+                PathBuf::from("/a/.claude"),
+                PathBuf::from("/b \"x\"/.claude"),
+            ],
+            2048,
+            DeletionMethod::Permanent,
+        );
+        record.timestamp = "2024-03-01T12:30:00Z".parse().unwrap();
+        history.add(record);
+
+        let csv = history.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("Timestamp,Method,Folders,Size (bytes),Size (human),Paths")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("2024-03-01 12:30:00,Permanent,2,2048,2.0 KB,\"/a/.claude;/b \"\"x\"\"/.claude\"")
+        );
+
+        let json: Vec<DeletionRecord> = serde_json::from_str(&history.to_json()).unwrap();
+        assert_eq!(json.len(), 1);
+        assert_eq!(json[0].total_size, 2048);
+    }
+
     #[test]
     fn test_history_path_not_empty() {
         let path = History::history_path();
@@ -1,22 +1,29 @@
 mod app;
 mod config;
+mod diff;
 mod filter;
 mod history;
 mod project;
 mod report;
 mod scanner;
+mod state;
 mod trash;
+mod treemap;
 mod tui;
 mod ui;
 mod utils;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use config::Config;
 use history::{DeletionMethod, DeletionRecord, History};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::TryRecvError;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[derive(Parser, Debug)]
 #[command(name = "claudekill")]
@@ -26,18 +33,107 @@ pub struct Args {
     #[arg(short, long)]
     path: Option<String>,
 
+    /// Maximum directory depth to scan (0 = only the root itself)
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Threads used to walk and size folders (1 = sequential, kinder to
+    /// spinning disks; default: one per CPU)
+    #[arg(short, long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: Option<u16>,
+
+    /// Skip folders smaller than this size (e.g. 10MB, 500K)
+    #[arg(long, value_name = "SIZE")]
+    min_size: Option<String>,
+
     /// List folders without interactive TUI
     #[arg(long)]
     dry_run: bool,
 
+    /// Order of --dry-run output and the folder list of CSV and JSON
+    /// reports: size_desc, size_asc, name_asc, name_desc, date_desc,
+    /// date_asc, disk_size_desc, file_count_desc (also sets the TUI's
+    /// starting order)
+    #[arg(long, value_name = "ORDER")]
+    sort: Option<filter::SortOrder>,
+
+    /// With --dry-run, print found folders as a JSON array
+    #[arg(long)]
+    json: bool,
+
+    /// Print each folder as a JSON line as soon as it is found, without the
+    /// TUI (pairs well with --no-size)
+    #[arg(long, conflicts_with_all = ["dry_run", "report", "delete_all"])]
+    stream: bool,
+
+    /// With --dry-run: table (aligned, paths shortened) or plain
+    /// (one `SIZE<TAB>PATH<TAB>TYPE` line per folder, size in bytes)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_parser = ["table", "plain"],
+        default_value = "table",
+        conflicts_with = "json"
+    )]
+    format: String,
+
+    /// With --dry-run, --report or --stream, only find and count folders
+    /// without measuring their sizes (much faster on slow filesystems)
+    #[arg(long, alias = "count", conflicts_with = "min_size")]
+    no_size: bool,
+
+    /// Measure allocated disk blocks instead of file lengths, so totals and
+    /// the reclaimed figure match `df` (Unix)
+    #[arg(long)]
+    disk_usage: bool,
+
+    /// Measure file lengths even if the config enables disk_usage (default)
+    #[arg(long, conflicts_with = "disk_usage")]
+    apparent_size: bool,
+
+    /// Skip paths matching a glob or containing a substring (repeatable; adds
+    /// to config exclude_patterns)
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+
     /// Include global ~/.claude folder
     #[arg(long)]
     include_global: bool,
 
+    /// Skip global ~/.claude folder even if the config includes it
+    #[arg(long, conflicts_with = "include_global")]
+    exclude_global: bool,
+
+    /// Also find stray `.claude` files (not just directories)
+    #[arg(long)]
+    include_files: bool,
+
+    /// Leave the most recently modified folder among siblings out of
+    /// select-all and --delete-all
+    #[arg(long)]
+    keep_active: bool,
+
     /// Permanently delete instead of moving to Trash
     #[arg(long)]
     permanent: bool,
 
+    /// Move deleted folders into this directory instead of the Trash
+    #[arg(long, value_name = "DIR")]
+    quarantine: Option<PathBuf>,
+
+    /// Write a .tar.gz of each folder into this directory before permanently deleting it
+    /// (only used for permanent deletes)
+    #[arg(long, value_name = "DIR")]
+    backup: Option<PathBuf>,
+
+    /// Show result count and total size in the terminal window title
+    #[arg(long)]
+    window_title: bool,
+
+    /// Draw the TUI without colors (also when NO_COLOR is set)
+    #[arg(long)]
+    no_color: bool,
+
     /// Create default config file
     #[arg(long)]
     init_config: bool,
@@ -46,96 +142,586 @@ pub struct Args {
     #[arg(long)]
     config_path: bool,
 
-    /// Undo last trash-based deletion
-    #[arg(long)]
-    undo: bool,
+    /// Read settings from this file instead of the default config location
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Undo the last trash/quarantine deletion, or entry N from --history
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "0")]
+    undo: Option<usize>,
 
     /// Show deletion history
     #[arg(long)]
     history: bool,
 
+    /// With --history, print lifetime totals instead of the entries
+    #[arg(
+        long,
+        alias = "stats-only",
+        requires = "history",
+        conflicts_with = "export"
+    )]
+    stats: bool,
+
+    /// Delete recorded deletion history
+    #[arg(long)]
+    clear_history: bool,
+
+    /// Only show folders (or, with --clear-history, entries) older than this
+    /// (e.g. 30d, 2w, 6mo, 1y)
+    #[arg(long, value_name = "DURATION")]
+    older_than: Option<String>,
+
+    /// With --older-than, also keep folders whose age is unknown
+    #[arg(long, requires = "older_than")]
+    include_unknown_age: bool,
+
+    /// Delete every matching folder without the TUI (combine with filters
+    /// like --older-than; asks first unless --yes)
+    #[arg(long, conflicts_with_all = ["dry_run", "report"])]
+    delete_all: bool,
+
+    /// Skip confirmation prompts
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Print each path skipped because it could not be read
+    #[arg(short, long)]
+    verbose: bool,
+
     /// Generate space analysis report
     #[arg(long)]
     report: bool,
 
-    /// Export format: json, csv
+    /// Export format: json, csv, markdown, html (with --history: json, csv)
     #[arg(long, value_name = "FORMAT")]
     export: Option<String>,
+
+    /// Write the exported report to a file instead of stdout (format
+    /// inferred from the extension when --export is omitted)
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// With --report, show how total space changed over the last N reports
+    /// (default 10) instead of scanning
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        requires = "report"
+    )]
+    trend: Option<usize>,
+
+    /// Group the report's by-directory totals this many levels below the
+    /// scan root (e.g. 1 = ~/work, ~/personal)
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    group_depth: usize,
+
+    /// Split the report's age breakdown at these ages (e.g. 90d,180d,1y;
+    /// default 1w,1mo,3mo)
+    #[arg(long, value_name = "LIST", value_delimiter = ',', requires = "report")]
+    age_buckets: Option<Vec<String>>,
+
+    /// Scan and show which folders were added, removed, grew or shrank since
+    /// a saved --report --export json, --dry-run --json or --stream file
+    #[arg(
+        long,
+        value_name = "SNAPSHOT_FILE",
+        conflicts_with_all = ["report", "dry_run", "stream", "delete_all"]
+    )]
+    diff: Option<PathBuf>,
+
+    /// Periodically write partial report results to --output during long scans
+    #[arg(long, value_name = "SECONDS", requires = "output")]
+    checkpoint: Option<u64>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    // Handle config-related commands first
+    // Handle config-related commands first (--config overrides the location)
+    let config_file = args.config.clone().unwrap_or_else(Config::config_path);
     if args.config_path {
-        println!("{}", Config::config_path().display());
+        println!("{}", config_file.display());
         return Ok(());
     }
 
     if args.init_config {
-        match Config::create_default_if_missing() {
-            Ok(true) => println!("Created config at: {}", Config::config_path().display()),
-            Ok(false) => println!("Config already exists: {}", Config::config_path().display()),
+        match Config::create_default_if_missing(&config_file) {
+            Ok(true) => println!("Created config at: {}", config_file.display()),
+            Ok(false) => println!("Config already exists: {}", config_file.display()),
             Err(e) => eprintln!("Failed to create config: {}", e),
         }
         return Ok(());
     }
 
+    // An explicit config that isn't there is a mistake, not "use defaults"
+    if args.config.is_some() && !config_file.is_file() {
+        anyhow::bail!("Config file not found: {}", config_file.display());
+    }
+
     // Handle undo command
-    if args.undo {
-        return handle_undo();
+    if let Some(index) = args.undo {
+        return handle_undo(index);
+    }
+
+    let older_than = args
+        .older_than
+        .as_deref()
+        .map(utils::parse_duration)
+        .transpose()?;
+
+    // Handle clear-history command
+    if args.clear_history {
+        return handle_clear_history(older_than, args.yes);
     }
 
     // Handle history command
     if args.history {
-        return handle_history();
+        return handle_history(args.stats, args.export.as_deref());
     }
 
     // Load config (with graceful fallback to defaults)
-    let config = Config::load().unwrap_or_else(|e| {
+    let config = Config::load_from(&config_file).unwrap_or_else(|e| {
         eprintln!("Warning: Failed to load config: {}", e);
         Config::default()
     });
 
-    // Determine root directory (CLI arg > config > home)
-    let root = match &args.path {
-        Some(p) => PathBuf::from(p),
+    // Determine root directories (CLI arg > config > home)
+    let scanning_home = args.path.is_none() && config.scan.default_paths.is_empty();
+    let roots: Vec<PathBuf> = match &args.path {
+        Some(p) => vec![utils::expand_tilde(Path::new(p))],
         None => {
             if !config.scan.default_paths.is_empty() {
-                config.scan.default_paths[0].clone()
+                config
+                    .scan
+                    .default_paths
+                    .iter()
+                    .map(|p| utils::expand_tilde(p))
+                    .collect()
             } else {
-                dirs::home_dir().expect("Could not find home directory")
+                vec![dirs::home_dir().expect("Could not find home directory")]
             }
         }
     };
 
     // Merge CLI flags with config (CLI takes precedence)
-    let include_global = args.include_global || config.scan.include_global;
+    let include_global =
+        !args.exclude_global && (args.include_global || config.scan.include_global);
+    let disk_usage = !args.apparent_size && (args.disk_usage || config.scan.disk_usage);
     let permanent = args.permanent || config.behavior.permanent_delete;
-    let exclude_patterns = config.scan.exclude_patterns.clone();
+    let window_title = args.window_title || config.display.window_title;
+    let mut exclude_patterns = config.scan.exclude_patterns.clone();
+    exclude_patterns.extend(args.exclude.iter().cloned());
+    let max_depth = args.max_depth.or(config.scan.max_depth);
+    let jobs = args
+        .jobs
+        .map(usize::from)
+        .or(config.scan.jobs)
+        .filter(|&n| n > 0);
+    if args.no_size && !(args.dry_run || args.report || args.stream) {
+        anyhow::bail!("--no-size only applies to --dry-run, --report and --stream");
+    }
+    if let Some(sort) = args.sort.filter(|s| args.no_size && s.uses_sizes()) {
+        anyhow::bail!(
+            "--sort {} needs folder sizes; drop --no-size or sort by name or date",
+            sort.name()
+        );
+    }
+    // Without sizes, listings default to path order
+    let list_sort = args.sort.unwrap_or(if args.no_size {
+        filter::SortOrder::NameAsc
+    } else {
+        filter::SortOrder::default()
+    });
+    // Nothing would pass a size threshold when sizes aren't measured
+    let min_size = args
+        .min_size
+        .as_deref()
+        .or(config.scan.min_size.as_deref())
+        .filter(|_| !args.no_size)
+        .map(utils::parse_size)
+        .transpose()?;
+    let age_filter = filter::Filter::older_than(older_than, args.include_unknown_age);
+
+    let scanner = scanner::Scanner::new(roots, include_global, exclude_patterns)
+        .with_max_depth(max_depth)
+        .with_jobs(jobs)
+        .with_min_size(min_size)
+        .with_custom_markers(config.project.custom_markers.clone())
+        .with_max_ancestors(config.project.max_ancestors)
+        .with_follow_symlinks(config.scan.follow_symlinks)
+        .with_respect_gitignore(config.scan.respect_gitignore)
+        .with_size_cache(config.scan.cache_sizes.then(state::SizeCache::cache_path))
+        .with_skip_sizes(args.no_size)
+        .with_disk_usage(disk_usage)
+        .with_include_files(args.include_files)
+        .with_skipped_dirs(args.quarantine.iter().cloned().collect());
 
     // Report mode - scan and generate report
+    if let Some(limit) = args.trend {
+        return handle_trend(&scanner, limit);
+    }
     if args.report {
-        return handle_report(
-            &root,
-            include_global,
-            &exclude_patterns,
-            args.export.as_deref(),
-        );
+        let age_buckets = match &args.age_buckets {
+            Some(edges) => edges
+                .iter()
+                .map(|s| utils::parse_duration(s))
+                .collect::<Result<Vec<_>>>()?,
+            None => config
+                .report
+                .age_bucket_edges()
+                .context("Invalid age_buckets in config")?,
+        };
+        let options = ReportOptions {
+            export_format: args.export.as_deref(),
+            output: args.output.as_deref(),
+            checkpoint_interval: args.checkpoint.map(Duration::from_secs),
+            no_size: args.no_size,
+            group_depth: args.group_depth,
+            age_buckets: &age_buckets,
+            sort: list_sort,
+        };
+        return handle_report(&scanner, &options, &age_filter, args.verbose);
+    }
+
+    if let Some(snapshot) = &args.diff {
+        return handle_diff(&scanner, snapshot, &age_filter, args.verbose);
+    }
+
+    // Stream mode - JSON Lines as folders are found
+    if args.stream {
+        return stream(&scanner, &age_filter, args.verbose);
     }
 
     // Dry-run mode - just list without TUI
     if args.dry_run {
-        return dry_run(&root, include_global, &exclude_patterns);
+        let plain = args.format == "plain";
+        return dry_run(
+            &scanner,
+            &age_filter,
+            args.json,
+            plain,
+            args.no_size,
+            list_sort,
+            args.verbose,
+        );
+    }
+
+    // Scripted deletion - no TUI
+    let dirs = DeleteDirs {
+        quarantine: args.quarantine,
+        backup: args.backup,
+    };
+    if dirs.backup.is_some() && !permanent && args.delete_all {
+        anyhow::bail!("--backup only applies to permanent deletion; add --permanent");
+    }
+    if args.delete_all {
+        return handle_delete_all(
+            &scanner,
+            &age_filter,
+            &config,
+            deletion_method(permanent, &dirs),
+            &dirs,
+            DeleteAllOptions {
+                yes: args.yes,
+                keep_active: args.keep_active,
+            },
+            args.verbose,
+        );
+    }
+
+    // Nowhere to draw the TUI (piped, CI): list the folders like --dry-run
+    if !std::io::stdout().is_terminal() {
+        eprintln!("Output is not a terminal; listing folders instead of starting the TUI (pass --dry-run to skip this note).");
+        return dry_run(
+            &scanner,
+            &age_filter,
+            args.json,
+            args.format == "plain",
+            args.no_size,
+            list_sort,
+            args.verbose,
+        );
+    }
+
+    // First TUI run over all of ~: make sure that's what the user wants
+    if scanning_home && config.scan.confirm_home_scan && std::io::stdin().is_terminal() {
+        let mut state = state::State::load().unwrap_or_default();
+        if !state.home_scan_confirmed {
+            if !confirm("About to scan all of ~ — this may take a while. Continue?")? {
+                println!("Aborted. Use --path <DIR> to scan a specific directory.");
+                return Ok(());
+            }
+            state.home_scan_confirmed = true;
+            let _ = state.save();
+        }
     }
 
     // Interactive TUI mode
-    run_tui(&root, include_global, permanent, &config, &exclude_patterns)
+    let mut app = app::App::new_with_config(permanent, &config);
+    let (keymap, keymap_warnings) = ui::KeyMap::from_config(&config.keybinds);
+    for warning in &keymap_warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if !keymap_warnings.is_empty() {
+        app.message = Some(format!(
+            "{} keybinding problem(s) in config; see terminal output after quitting.",
+            keymap_warnings.len()
+        ));
+    }
+    app.keymap = keymap;
+    if args.no_color || ui::no_color_env() {
+        app.theme = ui::Theme::plain();
+    }
+    if config.display.remember_state {
+        if let Some(ui) = state::State::load().ok().and_then(|s| s.ui) {
+            app.sort_order = filter::SortOrder::from_name(&ui.sort_order).unwrap_or(app.sort_order);
+            app.show_filter_bar = ui.show_filter_bar;
+        }
+    }
+    if let Some(sort) = args.sort {
+        app.sort_order = sort;
+    }
+    app.filter.max_age = age_filter.max_age;
+    app.filter.include_unknown_age = age_filter.include_unknown_age;
+    app.keep_active = args.keep_active;
+    app.quarantine_dir = dirs.quarantine;
+    app.backup_dir = dirs.backup;
+    if app.backup_dir.is_some() && !app.permanent_delete {
+        app.message = Some(
+            "--backup only applies to permanent deletes; switch to permanent mode to use it"
+                .to_string(),
+        );
+    }
+    app.size_colors = config
+        .display
+        .size_color_thresholds()
+        .context("Invalid size_medium/size_large in config")?;
+    run_tui(
+        &scanner,
+        app,
+        window_title,
+        config.display.remember_state,
+        args.verbose,
+    )
+}
+
+/// Where deleted folders end up besides the Trash
+#[derive(Debug, Clone, Default)]
+struct DeleteDirs {
+    /// `--quarantine`: move folders here instead of the Trash
+    quarantine: Option<PathBuf>,
+    /// `--backup`: archive folders here before deleting them permanently
+    backup: Option<PathBuf>,
+}
+
+/// How folders will be removed given the delete mode settings
+fn deletion_method(permanent: bool, dirs: &DeleteDirs) -> DeletionMethod {
+    if permanent {
+        DeletionMethod::Permanent
+    } else if dirs.quarantine.is_some() {
+        DeletionMethod::Quarantine
+    } else {
+        DeletionMethod::Trash
+    }
+}
+
+/// Past-tense description of a deletion method for summaries
+fn method_verb(method: &DeletionMethod) -> &'static str {
+    match method {
+        DeletionMethod::Permanent => "Deleted",
+        DeletionMethod::Quarantine => "Quarantined",
+        DeletionMethod::Trash => "Moved to Trash",
+    }
+}
+
+/// Remove already-validated folders (path, size) and record the deletion
+/// in history, including the folders handled before a failure
+fn delete_folders(
+    folders: &[(PathBuf, u64)],
+    method: &DeletionMethod,
+    dirs: &DeleteDirs,
+    max_history_entries: usize,
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(trash::DeleteProgress),
+) -> Result<()> {
+    let paths: Vec<PathBuf> = folders.iter().map(|(path, _)| path.clone()).collect();
+    let mut record = DeletionRecord::new(paths.clone(), 0, method.clone());
+
+    let mut handled = 0;
+    let mut counting = |done: trash::DeleteProgress| {
+        handled = done.folders;
+        progress(done);
+    };
+    let result = match (method, dirs.quarantine.as_deref()) {
+        (DeletionMethod::Permanent, _) => {
+            // Nothing is deleted unless every archive was written
+            if let Some(dir) = &dirs.backup {
+                record.backup_paths = trash::backup_folders(&paths, dir)?;
+            }
+            trash::permanent_delete(&paths, cancel, &mut counting)
+        }
+        (DeletionMethod::Quarantine, Some(dir)) => trash::move_to_quarantine(
+            &paths,
+            dir,
+            &mut record.quarantine_paths,
+            cancel,
+            &mut counting,
+        ),
+        _ => trash::move_to_trash(&paths, cancel, &mut counting)
+            .map(|items| record.trash_items = items),
+    };
+
+    // Folders handled before a failure are gone all the same
+    record.paths.truncate(handled);
+    record.backup_paths.truncate(handled);
+    record.quarantine_paths.truncate(handled);
+    record.total_size = folders[..handled].iter().map(|(_, size)| size).sum();
+    if handled > 0 {
+        if let Ok(hist) = History::load() {
+            let mut hist = hist.with_max_entries(max_history_entries);
+            hist.add(record);
+            let _ = hist.save();
+        }
+    }
+
+    if handled < folders.len() {
+        result.with_context(|| format!("Stopped after {} of {} folder(s)", handled, folders.len()))
+    } else {
+        result
+    }
+}
+
+/// How --delete-all picks and confirms folders
+#[derive(Clone, Copy)]
+struct DeleteAllOptions {
+    /// `--yes`: don't ask before deleting
+    yes: bool,
+    /// `--keep-active`: leave the freshest folder among siblings alone
+    keep_active: bool,
+}
+
+/// Handle --delete-all: delete every matching folder without the TUI
+fn handle_delete_all(
+    scanner: &scanner::Scanner,
+    filter: &filter::Filter,
+    config: &Config,
+    method: DeletionMethod,
+    dirs: &DeleteDirs,
+    options: DeleteAllOptions,
+    verbose: bool,
+) -> Result<()> {
+    let DeleteAllOptions { yes, keep_active } = options;
+    println!("Scanning: {}", scanner.roots_display());
+
+    let handle = scanner.scan();
+    let results = report::collect_folders(&handle.rx, None)?;
+    if verbose {
+        print_scan_errors(&results.errors);
+    }
+
+    let safety = &config.safety;
+    let protected = safety.expanded_protected_paths();
+    let (skipped, folders): (Vec<_>, Vec<_>) = results
+        .folders
+        .iter()
+        .filter(|f| filter.matches(f))
+        .cloned()
+        .partition(|f| trash::is_protected(&f.path, &protected));
+    if !skipped.is_empty() {
+        println!("Skipping {} protected folder(s).", skipped.len());
+    }
+
+    // Nobody is around to confirm deleting folders that look in use
+    let (recent, folders): (Vec<_>, Vec<_>) = folders
+        .into_iter()
+        .partition(|f| trash::is_recently_modified(f.modified_at, safety.recent_window()));
+    if !recent.is_empty() {
+        println!(
+            "Skipping {} folder(s) modified in the last {} day(s).",
+            recent.len(),
+            safety.protect_recent_days
+        );
+    }
+
+    let folders = if keep_active {
+        let (active, folders) = filter::partition_active(folders, &results.folders);
+        if !active.is_empty() {
+            println!("Skipping {} active folder(s).", active.len());
+        }
+        folders
+    } else {
+        folders
+    };
+    if folders.is_empty() {
+        println!("No matching .claude folders found.");
+        return Ok(());
+    }
+
+    let paths: Vec<PathBuf> = folders.iter().map(|f| f.path.clone()).collect();
+    let size: u64 = folders.iter().map(|f| f.size).sum();
+
+    println!();
+    for folder in &folders {
+        println!("{:>10}  {}", folder.size_display(), folder.path.display());
+    }
+    println!();
+
+    let prompt = format!(
+        "{} {} folder(s) ({})?",
+        match method {
+            DeletionMethod::Permanent => "PERMANENTLY delete",
+            DeletionMethod::Quarantine => "Quarantine",
+            DeletionMethod::Trash => "Move to Trash",
+        },
+        paths.len(),
+        utils::format_size(size)
+    );
+    if !yes && !confirm(&prompt)? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    trash::validate_deletion(&paths, &protected, scanner.include_files())
+        .context("Safety check failed")?;
+    let targets: Vec<(PathBuf, u64)> = folders.iter().map(|f| (f.path.clone(), f.size)).collect();
+    delete_folders(
+        &targets,
+        &method,
+        dirs,
+        config.behavior.max_history_entries,
+        &AtomicBool::new(false),
+        &mut |_| {},
+    )?;
+
+    println!(
+        "{} {} folder(s). {} reclaimed.",
+        method_verb(&method),
+        paths.len(),
+        utils::format_size(size)
+    );
+    Ok(())
+}
+
+/// Print paths skipped during a scan to stderr
+fn print_scan_errors(errors: &[(PathBuf, String)]) {
+    for (path, message) in errors {
+        eprintln!("Skipped {}: {}", path.display(), message);
+    }
 }
 
 /// Handle --undo command
-fn handle_undo() -> Result<()> {
-    match history::undo_last()? {
+fn handle_undo(index: usize) -> Result<()> {
+    // Entries are numbered from 1 in --history; 0 means "most recent"
+    let result = if index == 0 {
+        history::undo_last()?
+    } else {
+        Some(history::undo_record(index - 1)?)
+    };
+
+    match result {
         Some(restored) if !restored.is_empty() => {
             println!("Restored {} folder(s):", restored.len());
             for path in restored {
@@ -147,33 +733,98 @@ fn handle_undo() -> Result<()> {
         }
         None => {
             println!("No undoable deletion found.");
-            println!("Note: Only trash-based deletions can be undone.");
+            println!(
+                "Note: Only trash, quarantine and backed-up permanent deletions can be undone."
+            );
         }
     }
     Ok(())
 }
 
+/// Handle --clear-history command
+fn handle_clear_history(older_than: Option<Duration>, yes: bool) -> Result<()> {
+    let mut hist = History::load()?;
+
+    let count = hist.clone().clear(older_than);
+    if count == 0 {
+        println!("No history entries to remove.");
+        return Ok(());
+    }
+
+    if !yes && !confirm(&format!("Remove {} history entries?", count))? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let removed = hist.clear(older_than);
+    hist.save()?;
+    println!("Removed {} history entries.", removed);
+    Ok(())
+}
+
+/// Ask a yes/no question on stdin (default no)
+fn confirm(prompt: &str) -> Result<bool> {
+    use std::io::Write;
+
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
 /// Handle --history command
-fn handle_history() -> Result<()> {
+fn handle_history(stats: bool, export_format: Option<&str>) -> Result<()> {
     let hist = History::load()?;
 
+    match export_format {
+        Some("json") => {
+            println!("{}", hist.to_json());
+            return Ok(());
+        }
+        Some("csv") => {
+            print!("{}", hist.to_csv());
+            return Ok(());
+        }
+        Some(other) => {
+            anyhow::bail!(
+                "Unknown history export format: {}. Use 'json' or 'csv'.",
+                other
+            )
+        }
+        None => {}
+    }
+
     if hist.records.is_empty() {
         println!("No deletion history.");
         return Ok(());
     }
 
+    if stats {
+        print_history_stats(&hist.stats());
+        return Ok(());
+    }
+
     println!("Deletion History (most recent first):");
     println!("{:-<70}", "");
 
-    for record in hist.records.iter().rev().take(20) {
+    for (i, record) in hist.records.iter().enumerate().rev().take(20) {
         let method = match record.method {
             DeletionMethod::Trash => "Trash",
+            DeletionMethod::Quarantine => "Quarantine",
             DeletionMethod::Permanent => "Permanent",
         };
-        let undo_marker = if record.can_undo() { " [undoable]" } else { "" };
+        let undo_marker = if record.can_undo() {
+            " [undoable]"
+        } else if !record.backup_paths.is_empty() {
+            " [backup missing]"
+        } else {
+            ""
+        };
 
         println!(
-            "{}  {:>4} folder(s)  {:>10}  ({}){}",
+            "#{:<4} {}  {:>4} folder(s)  {:>10}  ({}){}",
+            i + 1,
             record.timestamp.format("%Y-%m-%d %H:%M"),
             record.paths.len(),
             utils::format_size(record.total_size),
@@ -186,92 +837,339 @@ fn handle_history() -> Result<()> {
         println!("... and {} more entries", hist.records.len() - 20);
     }
 
+    println!();
+    println!("Restore a specific entry with: claudekill --undo <#>");
+
+    Ok(())
+}
+
+/// Print --history --stats
+fn print_history_stats(stats: &history::HistoryStats) {
+    let date = |at: Option<chrono::DateTime<chrono::Utc>>| {
+        at.map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    };
+
+    println!("Deletion History Totals:");
+    println!("{:-<40}", "");
+    println!("Operations:      {}", stats.operations);
+    println!(
+        "Folders:         {} ({:.1} per operation)",
+        stats.folders,
+        stats.average_folders()
+    );
+    println!("Reclaimed:       {}", utils::format_size(stats.total_size));
+    println!("  Trash:         {}", utils::format_size(stats.trash_size));
+    println!(
+        "  Permanent:     {}",
+        utils::format_size(stats.permanent_size)
+    );
+    if stats.quarantine_size > 0 {
+        println!(
+            "  Quarantine:    {}",
+            utils::format_size(stats.quarantine_size)
+        );
+    }
+    println!(
+        "Period:          {} to {}",
+        date(stats.first),
+        date(stats.last)
+    );
+}
+
+/// Handle --report --trend: totals of past reports over the same roots
+fn handle_trend(scanner: &scanner::Scanner, limit: usize) -> Result<()> {
+    let roots = scanner.roots_display();
+    let snapshots =
+        state::TrendSnapshot::load_recent_from(&state::TrendSnapshot::trend_path(), &roots, limit);
+
+    if snapshots.is_empty() {
+        println!("No reports recorded for {} yet. Run --report first.", roots);
+        return Ok(());
+    }
+
+    println!("Space trend for {}:\n", roots);
+    println!(
+        "{:16}  {:>7}  {:>10}  {:>11}",
+        "DATE", "FOLDERS", "SIZE", "CHANGE"
+    );
+    println!("{}", "-".repeat(50));
+    let mut previous: Option<u64> = None;
+    for snapshot in &snapshots {
+        let change = match previous {
+            Some(prev) if snapshot.total_size >= prev => {
+                format!("+{}", utils::format_size(snapshot.total_size - prev))
+            }
+            Some(prev) => format!("-{}", utils::format_size(prev - snapshot.total_size)),
+            None => String::new(),
+        };
+        println!(
+            "{:16}  {:>7}  {:>10}  {:>11}",
+            snapshot
+                .timestamp
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M"),
+            snapshot.total_folders,
+            utils::format_size(snapshot.total_size),
+            change
+        );
+        previous = Some(snapshot.total_size);
+    }
+
+    let sizes: Vec<u64> = snapshots.iter().map(|s| s.total_size).collect();
+    println!("\nTrend: {}", utils::sparkline(&sizes));
     Ok(())
 }
 
+/// How --report output is produced
+#[derive(Clone, Copy)]
+struct ReportOptions<'a> {
+    export_format: Option<&'a str>,
+    output: Option<&'a Path>,
+    checkpoint_interval: Option<Duration>,
+    no_size: bool,
+    group_depth: usize,
+    age_buckets: &'a [Duration],
+    sort: filter::SortOrder,
+}
+
 /// Handle --report command
 fn handle_report(
-    root: &Path,
-    include_global: bool,
-    exclude_patterns: &[String],
-    export_format: Option<&str>,
+    scanner: &scanner::Scanner,
+    options: &ReportOptions,
+    filter: &filter::Filter,
+    verbose: bool,
 ) -> Result<()> {
-    println!("Scanning: {}", root.display());
+    let ReportOptions {
+        export_format,
+        output,
+        checkpoint_interval,
+        no_size,
+        group_depth,
+        age_buckets,
+        sort,
+    } = *options;
 
-    let scanner = scanner::Scanner::new(
-        root.to_path_buf(),
-        include_global,
-        exclude_patterns.to_vec(),
-    );
-    let rx = scanner.scan();
-
-    let mut folders = Vec::new();
-    for event in rx {
-        match event {
-            scanner::ScanEvent::Found(folder) => folders.push(folder),
-            scanner::ScanEvent::Complete => break,
-            _ => {}
+    // Keep stdout clean when an export is printed for redirecting
+    if export_format.is_some() && output.is_none() {
+        eprintln!("Scanning: {}", scanner.roots_display());
+    } else {
+        println!("Scanning: {}", scanner.roots_display());
+    }
+
+    // Files always get a machine-readable format
+    let file_format = export_format
+        .or_else(|| output.and_then(report::format_for_path))
+        .unwrap_or("json");
+    if let Some(path) = output {
+        if !report::is_export_format(file_format) {
+            anyhow::bail!(
+                "Unknown export format: {}. Use 'json', 'csv', 'markdown' or 'html'.",
+                file_format
+            );
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
         }
     }
 
-    let report = report::SpaceReport::generate(&folders);
+    let mut checkpoint = match (output, checkpoint_interval) {
+        (Some(path), Some(interval)) => Some(
+            report::Checkpoint::new(path.to_path_buf(), file_format, interval)
+                .with_age_buckets(age_buckets.to_vec()),
+        ),
+        _ => None,
+    };
+
+    let handle = scanner.scan();
+    let results = report::collect_folders(&handle.rx, checkpoint.as_mut())?;
+    if verbose {
+        print_scan_errors(&results.errors);
+    }
+
+    let folders: Vec<_> = results
+        .folders
+        .into_iter()
+        .filter(|f| filter.matches(f))
+        .collect();
+    let mut report = report::SpaceReport::generate(&folders, age_buckets);
+    report.skipped_paths = results.errors.len();
+    report.group_by_root_dir(scanner.roots(), group_depth);
+    report.sort_folders(&folders, sort);
+
+    // Only complete, unfiltered totals are comparable across runs
+    if !no_size && !filter.is_active() {
+        let snapshot = state::TrendSnapshot {
+            timestamp: chrono::Utc::now(),
+            roots: scanner.roots_display(),
+            total_folders: report.total_folders,
+            total_size: report.total_size,
+        };
+        if let Err(e) = snapshot.append_to(&state::TrendSnapshot::trend_path()) {
+            eprintln!("Warning: Failed to record trend snapshot: {}", e);
+        }
+    }
+
+    if let Some(path) = output {
+        let content = report.export(file_format).unwrap_or_default();
+        utils::write_atomic(path, &content)
+            .with_context(|| format!("Failed to write report: {}", path.display()))?;
+        println!("Wrote report to {}", path.display());
+        return Ok(());
+    }
 
     match export_format {
         Some("json") => println!("{}", report.to_json()),
         Some("csv") => print!("{}", report.to_csv()),
-        Some(fmt) => eprintln!("Unknown export format: {}. Use 'json' or 'csv'.", fmt),
+        Some("markdown" | "md") => print!("{}", report.to_markdown()),
+        Some("html") => print!("{}", report.to_html()),
+        Some(fmt) => eprintln!(
+            "Unknown export format: {}. Use 'json', 'csv', 'markdown' or 'html'.",
+            fmt
+        ),
+        None if no_size => report.print_counts(),
         None => report.print_summary(),
     }
 
     Ok(())
 }
 
-/// Dry-run mode: scan and list all .claude folders without TUI
-fn dry_run(root: &Path, include_global: bool, exclude_patterns: &[String]) -> Result<()> {
-    println!("Scanning: {}", root.display());
-    println!();
-
-    let scanner = scanner::Scanner::new(
-        root.to_path_buf(),
-        include_global,
-        exclude_patterns.to_vec(),
+/// Handle --diff command
+fn handle_diff(
+    scanner: &scanner::Scanner,
+    snapshot: &Path,
+    filter: &filter::Filter,
+    verbose: bool,
+) -> Result<()> {
+    let before = diff::load_snapshot(snapshot)?;
+    println!(
+        "Comparing {} with {} ({} folders)\n",
+        scanner.roots_display(),
+        snapshot.display(),
+        before.len()
     );
-    let rx = scanner.scan();
 
-    let mut folders = Vec::new();
+    let handle = scanner.scan();
+    let results = report::collect_folders(&handle.rx, None)?;
+    if verbose {
+        print_scan_errors(&results.errors);
+    }
+    let folders: Vec<_> = results
+        .folders
+        .into_iter()
+        .filter(|f| filter.matches(f))
+        .collect();
 
-    for event in rx {
-        match event {
-            scanner::ScanEvent::Found(folder) => {
-                folders.push(folder);
-            }
-            scanner::ScanEvent::Complete => {
-                break;
+    diff::ScanDiff::compare(&before, &folders).print();
+    Ok(())
+}
+
+/// Stream mode: print folders as JSON Lines while the scan runs
+fn stream(scanner: &scanner::Scanner, filter: &filter::Filter, verbose: bool) -> Result<()> {
+    let handle = scanner.scan();
+    let mut out = std::io::stdout().lock();
+    match report::stream_folders(&handle.rx, &mut out, |f| filter.matches(f)) {
+        Ok(errors) => {
+            if verbose {
+                print_scan_errors(&errors);
             }
-            _ => {}
+            Ok(())
+        }
+        // The reader went away (e.g. `| head`): stop scanning quietly
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+            handle.cancel();
+            Ok(())
         }
+        Err(e) => Err(e).context("Failed to write to stdout"),
+    }
+}
+
+/// Dry-run mode: scan and list all .claude folders without TUI
+fn dry_run(
+    scanner: &scanner::Scanner,
+    filter: &filter::Filter,
+    json: bool,
+    plain: bool,
+    no_size: bool,
+    sort: filter::SortOrder,
+    verbose: bool,
+) -> Result<()> {
+    // Keep stdout pure JSON or plain lines when piping
+    if !json && !plain {
+        println!("Scanning: {}", scanner.roots_display());
+        println!();
+    }
+
+    let started = Instant::now();
+    let handle = scanner.scan();
+    let results = report::collect_folders(&handle.rx, None)?;
+    let elapsed = started.elapsed();
+    let mut folders = results.folders;
+    folders.retain(|f| filter.matches(f));
+
+    if verbose {
+        print_scan_errors(&results.errors);
     }
 
-    // Sort by size descending
-    folders.sort_by(|a, b| b.size.cmp(&a.size));
+    folders.sort_by(|a, b| sort.compare(a, b));
+
+    if json {
+        println!("{}", report::folders_to_json(&folders));
+        return Ok(());
+    }
+
+    // Untruncated and tab-separated for awk/cut; "-" when sizes weren't measured
+    if plain {
+        for folder in &folders {
+            let size = if no_size {
+                "-".to_string()
+            } else {
+                folder.size.to_string()
+            };
+            println!(
+                "{}\t{}\t{}",
+                size,
+                folder.path.display(),
+                folder.project_type
+            );
+        }
+        return Ok(());
+    }
+
+    if !results.errors.is_empty() {
+        println!(
+            "{} path(s) skipped (unreadable). Use --verbose to list them.\n",
+            results.errors.len()
+        );
+    }
 
     // Display results
     if folders.is_empty() {
         println!("No .claude folders found.");
+        print_scan_timing(elapsed, 0, handle.dirs_walked());
         return Ok(());
     }
 
     println!("Found {} .claude folder(s):\n", folders.len());
+
+    if no_size {
+        println!("{:62}  PROJECT", "PATH");
+        println!("{}", "-".repeat(80));
+        for folder in &folders {
+            println!("{:62}  {}", folder.path.display(), folder.project_type);
+        }
+        println!();
+        print_scan_timing(elapsed, folders.len(), handle.dirs_walked());
+        return Ok(());
+    }
+
     println!("{:>10}  {:50}  PROJECT", "SIZE", "PATH");
     println!("{}", "-".repeat(80));
 
     for folder in &folders {
         let path_str = folder.path.display().to_string();
-        let display_path = if path_str.len() > 50 {
-            format!("...{}", &path_str[path_str.len() - 47..])
-        } else {
-            path_str
-        };
+        let display_path = utils::truncate_start(&path_str, 50);
 
         println!(
             "{:>10}  {:50}  {}",
@@ -286,45 +1184,138 @@ fn dry_run(root: &Path, include_global: bool, exclude_patterns: &[String]) -> Re
     println!("{}", "-".repeat(80));
     println!("{:>10}  Total", utils::format_size(total_size));
 
+    println!();
+    println!("Reclaimable by age:");
+    for (label, size) in report::reclaimable_by_age(&folders) {
+        println!("{:>10}  {}", utils::format_size(size), label);
+    }
+
+    println!();
+    print_scan_timing(elapsed, folders.len(), handle.dirs_walked());
+
     Ok(())
 }
 
+/// Scan duration and counts, for tuning --max-depth/--exclude
+fn print_scan_timing(elapsed: Duration, found: usize, dirs_walked: usize) {
+    println!(
+        "Scanned in {:.1}s, found {} folder(s) ({} directories walked)",
+        elapsed.as_secs_f64(),
+        found,
+        dirs_walked
+    );
+}
+
+/// Messages from a deletion running on a background thread
+enum DeleteEvent {
+    Progress(trash::DeleteProgress),
+    Done(Result<()>),
+}
+
+/// A deletion started from the TUI that hasn't finished yet
+struct PendingDeletion {
+    rx: Receiver<DeleteEvent>,
+    /// Folders being deleted, with their sizes
+    folders: Vec<(PathBuf, u64)>,
+    method: DeletionMethod,
+    /// Set to stop the deletion after the current entry
+    cancel: Arc<AtomicBool>,
+}
+
+/// Run `delete_folders` on a background thread so the TUI keeps redrawing
+fn spawn_deletion(
+    folders: Vec<(PathBuf, u64)>,
+    method: DeletionMethod,
+    dirs: DeleteDirs,
+    max_history_entries: usize,
+) -> PendingDeletion {
+    let (tx, rx) = channel();
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let folders = folders.clone();
+        let method = method.clone();
+        let cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            let progress_tx = tx.clone();
+            let result = delete_folders(
+                &folders,
+                &method,
+                &dirs,
+                max_history_entries,
+                &cancel,
+                &mut |progress| {
+                    let _ = progress_tx.send(DeleteEvent::Progress(progress));
+                },
+            );
+            let _ = tx.send(DeleteEvent::Done(result));
+        });
+    }
+
+    PendingDeletion {
+        rx,
+        folders,
+        method,
+        cancel,
+    }
+}
+
 /// Interactive TUI mode
 fn run_tui(
-    root: &Path,
-    include_global: bool,
-    permanent: bool,
-    config: &Config,
-    exclude_patterns: &[String],
+    scanner: &scanner::Scanner,
+    mut app: app::App,
+    window_title: bool,
+    remember_state: bool,
+    verbose: bool,
 ) -> Result<()> {
+    // Scan durations are remembered per set of roots
+    let scan_key = std::env::join_paths(scanner.roots())
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
     // Initialize terminal
     let mut terminal = tui::init()?;
+    if window_title {
+        // Like pop_title below, a title error must not strand the terminal
+        let _ = tui::push_title();
+    }
+    let mut current_title = String::new();
 
-    // Initialize app state with config
-    let mut app = app::App::new_with_config(permanent, config);
+    app.last_scan_duration = state::State::load()
+        .ok()
+        .and_then(|s| s.last_scan_duration(&scan_key));
 
     // Start scanner in background
-    let scanner = scanner::Scanner::new(
-        root.to_path_buf(),
-        include_global,
-        exclude_patterns.to_vec(),
-    );
-    let rx = scanner.scan();
+    let mut handle = scanner.scan();
+    let mut scan_started = Instant::now();
+    let mut deletion: Option<PendingDeletion> = None;
+    // Re-measuring of the folders about to be deleted, see Action::Delete
+    let mut grown_check: Option<Receiver<Vec<PathBuf>>> = None;
+    let mut queued_action = None;
 
     // Main loop
     let result = (|| -> Result<()> {
         loop {
             // Process scanner events (non-blocking)
             loop {
-                match rx.try_recv() {
+                match handle.rx.try_recv() {
                     Ok(scanner::ScanEvent::Scanning(path)) => {
                         app.set_scanning(path);
                     }
                     Ok(scanner::ScanEvent::Found(folder)) => {
                         app.add_folder(folder);
                     }
+                    Ok(scanner::ScanEvent::Error(path, message)) => {
+                        app.scan_errors.push((path, message));
+                    }
                     Ok(scanner::ScanEvent::Complete) => {
+                        if handle.is_cancelled() {
+                            break;
+                        }
                         app.complete_scan();
+                        if let Ok(mut state) = state::State::load() {
+                            state.set_scan_duration(&scan_key, scan_started.elapsed());
+                            let _ = state.save();
+                        }
                     }
                     Err(TryRecvError::Empty) => break,
                     Err(TryRecvError::Disconnected) => {
@@ -336,76 +1327,181 @@ fn run_tui(
                 }
             }
 
+            // Process deletion progress (non-blocking)
+            let mut finished = None;
+            if let Some(pending) = &deletion {
+                loop {
+                    match pending.rx.try_recv() {
+                        Ok(DeleteEvent::Progress(progress)) => {
+                            if let Some(status) = &mut app.deletion {
+                                status.done = progress;
+                            }
+                        }
+                        Ok(DeleteEvent::Done(result)) => {
+                            finished = Some(result);
+                            break;
+                        }
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => {
+                            finished = Some(Err(anyhow::anyhow!("Deletion stopped unexpectedly")));
+                            break;
+                        }
+                    }
+                }
+            }
+            if let Some(result) = finished {
+                let pending = deletion.take().expect("finished deletion is pending");
+                app.deletion = None;
+                app.delete_target = None;
+                app.state = app::AppState::Browsing;
+                // After a failure, drop only the folders that are really gone
+                let deleted: Vec<PathBuf> = pending
+                    .folders
+                    .iter()
+                    .map(|(path, _)| path.clone())
+                    .filter(|path| result.is_ok() || path.symlink_metadata().is_err())
+                    .collect();
+                app.remove_deleted(&deleted);
+                match result {
+                    Ok(()) => {
+                        let size: u64 = pending.folders.iter().map(|(_, size)| size).sum();
+                        app.message = Some(format!(
+                            "{} {} folder(s). {} reclaimed.",
+                            method_verb(&pending.method),
+                            pending.folders.len(),
+                            utils::format_size(size)
+                        ));
+                    }
+                    Err(e) => app.message = Some(format!("Error: {:#}", e)),
+                }
+                // Ctrl+C during the deletion: quit now that it has stopped
+                if app.should_quit {
+                    break;
+                }
+            }
+
+            // Re-measuring finished: warn about folders that grew, or delete
+            let checked = match grown_check.as_ref().map(Receiver::try_recv) {
+                Some(Ok(grown)) => Some(Some(grown)),
+                Some(Err(TryRecvError::Disconnected)) => Some(None),
+                _ => None,
+            };
+            if let Some(grown) = checked {
+                grown_check = None;
+                app.message = None;
+                match grown {
+                    Some(grown) if !grown.is_empty() => {
+                        app.grown_since_scan = Some(grown);
+                        app.recent_confirmed = app.recent_target_count() > 0;
+                        app.open_confirm();
+                    }
+                    Some(grown) => {
+                        app.grown_since_scan = Some(grown);
+                        queued_action = Some(ui::Action::Delete);
+                    }
+                    None => {
+                        app.state = app::AppState::Browsing;
+                        app.delete_target = None;
+                        app.message = Some("Couldn't check the folders; nothing deleted".into());
+                    }
+                }
+            }
+
+            // Keep the window title in sync with results
+            if window_title {
+                let title = app.window_title();
+                if title != current_title {
+                    tui::set_title(&title)?;
+                    current_title = title;
+                }
+            }
+
             // Render UI
-            terminal.draw(|f| ui::render(f, &app))?;
+            app.on_tick();
+            terminal.draw(|f| ui::render(f, &mut app))?;
 
             // Handle input
-            let action = ui::handle_events(&mut app, Duration::from_millis(100))?;
+            let action = match queued_action.take() {
+                Some(action) => action,
+                None => ui::handle_events(&mut app, Duration::from_millis(100))?,
+            };
 
             match action {
-                ui::Action::Quit => break,
+                ui::Action::Quit => match &deletion {
+                    // Let the deletion stop and record what it did first
+                    Some(pending) => {
+                        pending.cancel.store(true, Ordering::Relaxed);
+                        app.message = Some("Stopping deletion...".to_string());
+                    }
+                    None => break,
+                },
+                ui::Action::CancelScan => {
+                    handle.cancel();
+                    app.cancel_scan();
+                }
+                ui::Action::Rescan => {
+                    handle = scanner.scan();
+                    scan_started = Instant::now();
+                    app.restart_scan();
+                }
                 ui::Action::Delete => {
                     // Collect paths to delete
-                    let folders: Vec<PathBuf> = app
-                        .get_selected_folders()
+                    let targets: Vec<(PathBuf, u64)> = app
+                        .deletion_targets()
                         .iter()
-                        .map(|f| f.path.clone())
+                        .map(|f| (f.path.clone(), f.size))
                         .collect();
-
-                    let deleted_size: u64 = app.get_selected_folders().iter().map(|f| f.size).sum();
+                    let folders: Vec<PathBuf> =
+                        targets.iter().map(|(path, _)| path.clone()).collect();
+                    let deleted_size: u64 = targets.iter().map(|(_, size)| size).sum();
 
                     // Safety validation before deletion
-                    if let Err(e) = trash::validate_deletion(&folders) {
+                    if let Err(e) = trash::validate_deletion(
+                        &folders,
+                        &app.protected_paths,
+                        scanner.include_files(),
+                    ) {
                         app.message = Some(format!("Safety check failed: {}", e));
                         app.state = app::AppState::Browsing;
+                        app.delete_target = None;
                         continue;
                     }
 
-                    // Perform deletion
-                    let deletion_method = if app.permanent_delete {
-                        DeletionMethod::Permanent
-                    } else {
-                        DeletionMethod::Trash
-                    };
+                    // Folders that grew since the scan may be in use: re-measure
+                    // them off the UI thread, then ask once more if any did
+                    if app.grown_since_scan.is_none() {
+                        let (tx, rx) = channel();
+                        let scanned: Vec<(PathBuf, u64)> = app
+                            .deletion_targets()
+                            .iter()
+                            .map(|f| (f.path.clone(), f.apparent_size))
+                            .collect();
+                        app.message = Some(format!(
+                            "Checking {} folder(s) for changes since the scan...",
+                            scanned.len()
+                        ));
+                        thread::spawn(move || {
+                            let _ = tx.send(scanner::grown_since_scan(&scanned));
+                        });
+                        grown_check = Some(rx);
+                        continue;
+                    }
+                    app.grown_since_scan = None;
 
-                    let result = if app.permanent_delete {
-                        trash::permanent_delete(&folders)
-                    } else {
-                        trash::move_to_trash(&folders)
+                    // Delete in the background; progress arrives on the next ticks
+                    let dirs = DeleteDirs {
+                        quarantine: app.quarantine_dir.clone(),
+                        backup: app.backup_dir.clone(),
                     };
-
-                    match result {
-                        Ok(()) => {
-                            // Record in history
-                            let record = DeletionRecord::new(
-                                folders.clone(),
-                                deleted_size,
-                                deletion_method.clone(),
-                            );
-                            if let Ok(mut hist) = History::load() {
-                                hist.add(record);
-                                let _ = hist.save();
-                            }
-
-                            let method = if app.permanent_delete {
-                                "Deleted"
-                            } else {
-                                "Moved to Trash"
-                            };
-                            app.remove_deleted(&folders);
-                            app.message = Some(format!(
-                                "{} {} folder(s). {} reclaimed.",
-                                method,
-                                folders.len(),
-                                utils::format_size(deleted_size)
-                            ));
-                            app.state = app::AppState::Browsing;
-                        }
-                        Err(e) => {
-                            app.message = Some(format!("Error: {}", e));
-                            app.state = app::AppState::Browsing;
-                        }
-                    }
+                    let method = deletion_method(app.permanent_delete, &dirs);
+                    app.deletion = Some(app::DeletionStatus::new(folders.len(), deleted_size));
+                    app.message = None;
+                    deletion = Some(spawn_deletion(
+                        targets,
+                        method,
+                        dirs,
+                        app.max_history_entries,
+                    ));
                 }
                 ui::Action::None => {}
             }
@@ -418,7 +1514,28 @@ fn run_tui(
     })();
 
     // Always restore terminal, even on error
+    if window_title {
+        // A failed title reset must not leave the terminal in raw mode
+        let _ = tui::pop_title();
+    }
     tui::restore()?;
 
+    if verbose {
+        print_scan_errors(&app.scan_errors);
+    }
+    for path in &app.copied_paths {
+        println!("{}", path.display());
+    }
+
+    if remember_state {
+        if let Ok(mut state) = state::State::load() {
+            state.ui = Some(state::UiState {
+                sort_order: app.sort_order.name().to_string(),
+                show_filter_bar: app.show_filter_bar,
+            });
+            let _ = state.save();
+        }
+    }
+
     result
 }
@@ -2,67 +2,293 @@
 
 use std::path::Path;
 
-/// Detect project type based on parent directory contents
-pub fn detect(claude_path: &Path) -> String {
-    let Some(parent) = claude_path.parent() else {
-        return "Unknown".to_string();
-    };
+/// Directories searched for markers by default: the parent of `.claude`
+/// and up to two levels above it
+pub const DEFAULT_MAX_ANCESTORS: usize = 3;
+
+/// Detect project type from the directories above a `.claude` folder
+///
+/// The parent is checked first, then further ancestors (at most
+/// `max_ancestors` directories in all, never above `root`), so a `.claude`
+/// nested in e.g. `config/` still gets its project's type; the nearest
+/// directory with a marker wins.
+///
+/// `custom_markers` maps a marker file (relative to the project dir) to a
+/// label. They are checked in order before the built-in markers, so the
+/// first matching entry wins.
+pub fn detect(
+    claude_path: &Path,
+    custom_markers: &[(String, String)],
+    root: Option<&Path>,
+    max_ancestors: usize,
+) -> String {
+    claude_path
+        .ancestors()
+        .skip(1)
+        .take(max_ancestors.max(1))
+        .enumerate()
+        .take_while(|(depth, dir)| *depth == 0 || root.is_none_or(|root| dir.starts_with(root)))
+        .find_map(|(_, dir)| detect_dir(dir, custom_markers))
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Project type of a single directory, from its marker files
+///
+/// Directories with markers of two ecosystems get a combined label
+/// ("Rust+Node.js"), and of more than two "Monorepo".
+fn detect_dir(parent: &Path, custom_markers: &[(String, String)]) -> Option<String> {
+    // User-defined markers take priority
+    if let Some((_, label)) = custom_markers
+        .iter()
+        .find(|(marker, _)| parent.join(marker).exists())
+    {
+        return Some(label.clone());
+    }
+
+    let types = builtin_types(parent);
+    match types.as_slice() {
+        [] => None,
+        [single] => Some(single.to_string()),
+        [first, second] => Some(format!("{}+{}", first, second)),
+        _ => Some("Monorepo".to_string()),
+    }
+}
+
+/// Every built-in ecosystem with a marker file in `parent`, in detection order
+fn builtin_types(parent: &Path) -> Vec<&'static str> {
+    let has = |marker: &str| parent.join(marker).exists();
+    let mut types = Vec::new();
 
     // Rust
-    if parent.join("Cargo.toml").exists() {
-        return "Rust".to_string();
+    if has("Cargo.toml") {
+        types.push("Rust");
     }
 
     // Node.js / JS ecosystem
-    if parent.join("package.json").exists() {
-        if parent.join("next.config.js").exists()
-            || parent.join("next.config.mjs").exists()
-            || parent.join("next.config.ts").exists()
-        {
-            return "Next.js".to_string();
-        }
-        if parent.join("nuxt.config.ts").exists() || parent.join("nuxt.config.js").exists() {
-            return "Nuxt".to_string();
-        }
-        if parent.join("vite.config.ts").exists() || parent.join("vite.config.js").exists() {
-            return "Vite".to_string();
-        }
-        if parent.join("angular.json").exists() {
-            return "Angular".to_string();
-        }
-        return "Node.js".to_string();
+    if has("package.json") {
+        types.push(
+            if has("next.config.js") || has("next.config.mjs") || has("next.config.ts") {
+                "Next.js"
+            } else if has("nuxt.config.ts") || has("nuxt.config.js") {
+                "Nuxt"
+            } else if has("svelte.config.js") {
+                "SvelteKit"
+            } else if has("astro.config.mjs") || has("astro.config.ts") {
+                "Astro"
+            } else if has("remix.config.js") {
+                "Remix"
+            } else if has("vite.config.ts") || has("vite.config.js") {
+                "Vite"
+            } else if has("angular.json") {
+                "Angular"
+            } else {
+                "Node.js"
+            },
+        );
+    } else if has("deno.json") || has("deno.jsonc") {
+        // Deno (no package.json)
+        types.push("Deno");
     }
 
     // Python
-    if parent.join("pyproject.toml").exists()
-        || parent.join("setup.py").exists()
-        || parent.join("requirements.txt").exists()
-    {
-        return "Python".to_string();
+    if has("pyproject.toml") || has("setup.py") || has("requirements.txt") {
+        types.push("Python");
     }
 
     // Go
-    if parent.join("go.mod").exists() {
-        return "Go".to_string();
+    if has("go.mod") {
+        types.push("Go");
     }
 
     // Flutter/Dart
-    if parent.join("pubspec.yaml").exists() {
-        return "Flutter".to_string();
+    if has("pubspec.yaml") {
+        types.push("Flutter");
     }
 
     // Ruby
-    if parent.join("Gemfile").exists() {
-        return "Ruby".to_string();
+    if has("Gemfile") {
+        types.push("Ruby");
     }
 
     // Java/Kotlin
-    if parent.join("pom.xml").exists()
-        || parent.join("build.gradle").exists()
-        || parent.join("build.gradle.kts").exists()
-    {
-        return "Java".to_string();
+    if has("pom.xml") || has("build.gradle") || has("build.gradle.kts") {
+        types.push("Java");
     }
 
-    "Unknown".to_string()
+    // PHP / Laravel
+    if has("composer.json") {
+        types.push(if has("artisan") { "Laravel" } else { "PHP" });
+    }
+
+    // .NET (project and solution files are named after the project)
+    if has_extension(parent, &["csproj", "fsproj", "sln"]) {
+        types.push(".NET");
+    }
+
+    // Elixir
+    if has("mix.exs") {
+        types.push("Elixir");
+    }
+
+    // Zig
+    if has("build.zig") {
+        types.push("Zig");
+    }
+
+    types
+}
+
+/// Whether `dir` directly contains a file with one of `extensions`
+fn has_extension(dir: &Path, extensions: &[&str]) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        entry
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.contains(&ext))
+            && entry.file_type().is_ok_and(|t| t.is_file())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Create a project with the given marker files and detect its type
+    fn detect_with(markers: &[&str]) -> String {
+        detect_with_custom(markers, &[])
+    }
+
+    fn detect_with_custom(markers: &[&str], custom: &[(String, String)]) -> String {
+        let temp = tempdir().unwrap();
+        for marker in markers {
+            let path = temp.path().join(marker);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let claude_path = temp.path().join(".claude");
+        fs::create_dir(&claude_path).unwrap();
+        detect(
+            &claude_path,
+            custom,
+            Some(temp.path()),
+            DEFAULT_MAX_ANCESTORS,
+        )
+    }
+
+    #[test]
+    fn test_detect_deno() {
+        assert_eq!(detect_with(&["deno.json"]), "Deno");
+        assert_eq!(detect_with(&["deno.jsonc"]), "Deno");
+    }
+
+    #[test]
+    fn test_detect_sveltekit() {
+        assert_eq!(
+            detect_with(&["package.json", "svelte.config.js", "vite.config.ts"]),
+            "SvelteKit"
+        );
+    }
+
+    #[test]
+    fn test_detect_astro() {
+        assert_eq!(detect_with(&["package.json", "astro.config.mjs"]), "Astro");
+        assert_eq!(detect_with(&["package.json", "astro.config.ts"]), "Astro");
+    }
+
+    #[test]
+    fn test_detect_remix() {
+        assert_eq!(detect_with(&["package.json", "remix.config.js"]), "Remix");
+    }
+
+    #[test]
+    fn test_detect_php_and_laravel() {
+        assert_eq!(detect_with(&["composer.json"]), "PHP");
+        assert_eq!(detect_with(&["composer.json", "artisan"]), "Laravel");
+    }
+
+    #[test]
+    fn test_detect_dotnet() {
+        assert_eq!(detect_with(&["MyApp.csproj"]), ".NET");
+        assert_eq!(detect_with(&["MyApp.sln"]), ".NET");
+        // Only files directly in the project dir count
+        assert_eq!(detect_with(&["src/Lib/Lib.csproj"]), "Unknown");
+    }
+
+    #[test]
+    fn test_detect_elixir() {
+        assert_eq!(detect_with(&["mix.exs"]), "Elixir");
+    }
+
+    #[test]
+    fn test_detect_zig() {
+        assert_eq!(detect_with(&["build.zig"]), "Zig");
+    }
+
+    #[test]
+    fn test_detect_mixed_ecosystems() {
+        assert_eq!(detect_with(&["Cargo.toml", "package.json"]), "Rust+Node.js");
+        assert_eq!(
+            detect_with(&["package.json", "next.config.js", "pyproject.toml"]),
+            "Next.js+Python"
+        );
+        assert_eq!(
+            detect_with(&["Cargo.toml", "package.json", "go.mod"]),
+            "Monorepo"
+        );
+        // Custom markers still win outright
+        let custom = vec![("Cargo.toml".to_string(), "Service".to_string())];
+        assert_eq!(
+            detect_with_custom(&["Cargo.toml", "package.json"], &custom),
+            "Service"
+        );
+    }
+
+    #[test]
+    fn test_detect_walks_up_to_root() {
+        let temp = tempdir().unwrap();
+        let project = temp.path().join("work/app");
+        let claude_path = project.join("config/.claude");
+        fs::create_dir_all(&claude_path).unwrap();
+        fs::write(project.join("Cargo.toml"), "").unwrap();
+        fs::write(temp.path().join("package.json"), "").unwrap();
+
+        // Found two levels up; nearer markers win over the one at the top
+        assert_eq!(detect(&claude_path, &[], None, 3), "Rust");
+        assert_eq!(detect(&claude_path, &[], None, 1), "Unknown");
+
+        // Never above the scan root
+        let config_dir = project.join("config");
+        assert_eq!(detect(&claude_path, &[], Some(&config_dir), 3), "Unknown");
+        assert_eq!(detect(&claude_path, &[], Some(&project), 3), "Rust");
+
+        fs::remove_file(project.join("Cargo.toml")).unwrap();
+        assert_eq!(detect(&claude_path, &[], None, 4), "Node.js");
+        assert_eq!(detect(&claude_path, &[], Some(&project), 4), "Unknown");
+    }
+
+    #[test]
+    fn test_detect_custom_markers_first_match_wins() {
+        let custom = vec![
+            (".meta/service.yaml".to_string(), "Service".to_string()),
+            ("Cargo.toml".to_string(), "Rust Service".to_string()),
+        ];
+        assert_eq!(
+            detect_with_custom(&[".meta/service.yaml", "Cargo.toml"], &custom),
+            "Service"
+        );
+        assert_eq!(detect_with_custom(&["Cargo.toml"], &custom), "Rust Service");
+        assert_eq!(detect_with_custom(&["go.mod"], &custom), "Go");
+    }
+
+    #[test]
+    fn test_detect_plain_node_and_unknown() {
+        assert_eq!(detect_with(&["package.json"]), "Node.js");
+        assert_eq!(detect_with(&[]), "Unknown");
+    }
 }
@@ -1,10 +1,17 @@
 //! Space analysis and report generation
 
-use crate::scanner::ClaudeFolder;
+use crate::filter::SortOrder;
+use crate::scanner::{ClaudeFolder, ScanEvent, ScanResults};
+use crate::treemap;
 use crate::utils::format_size;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Statistics for a project type
 #[derive(Debug, Serialize)]
@@ -14,22 +21,230 @@ pub struct TypeStats {
     pub avg_size: u64,
 }
 
-/// Age breakdown of folders
-#[derive(Debug, Serialize, Default)]
-pub struct AgeBreakdown {
-    pub under_1_week: usize,
-    pub under_1_month: usize,
-    pub under_3_months: usize,
-    pub over_3_months: usize,
+impl TypeStats {
+    fn from_sizes(sizes: impl Iterator<Item = u64>) -> Self {
+        let (count, total_size) = sizes.fold((0, 0), |(n, total), size| (n + 1, total + size));
+        Self {
+            count,
+            total_size,
+            avg_size: if count > 0 {
+                total_size / count as u64
+            } else {
+                0
+            },
+        }
+    }
 }
 
-/// Summary of a single folder
+/// Directory grouping a folder path: its scan root (the longest one that
+/// contains it) plus up to `depth` more components, never the `.claude`
+/// folder itself
+fn root_dir_group(path: &Path, roots: &[PathBuf], depth: usize) -> PathBuf {
+    let project = path.parent().unwrap_or(path);
+    let Some(root) = roots
+        .iter()
+        .filter(|r| project.starts_with(r))
+        .max_by_key(|r| r.components().count())
+    else {
+        return project.to_path_buf();
+    };
+
+    let mut group = root.clone();
+    if let Ok(rest) = project.strip_prefix(root) {
+        group.extend(rest.components().take(depth));
+    }
+    group
+}
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Default age bucket edges: a week, a month and 3 months
+pub const DEFAULT_AGE_BUCKETS: [Duration; 3] = [
+    Duration::from_secs(7 * DAY),
+    Duration::from_secs(30 * DAY),
+    Duration::from_secs(90 * DAY),
+];
+
+/// Folders in one age range of the breakdown
 #[derive(Debug, Serialize)]
+pub struct AgeBucket {
+    /// e.g. "< 1 week", or "> 3 months" for the oldest bucket
+    pub label: String,
+    /// Upper bound of the range in seconds (`None` for the oldest bucket)
+    pub max_age_secs: Option<u64>,
+    pub count: usize,
+}
+
+/// Index of the age bucket a folder falls in: the first edge it is younger
+/// than, or `edges.len()` if it is older than all of them.
+/// `None` if the age is unknown
+fn age_bucket(folder: &ClaudeFolder, now: SystemTime, edges: &[Duration]) -> Option<usize> {
+    let age = now.duration_since(folder.modified_at?).ok()?;
+    Some(
+        edges
+            .iter()
+            .position(|&edge| age < edge)
+            .unwrap_or(edges.len()),
+    )
+}
+
+/// A bucket edge in words, e.g. `1 week`, `3 months` or `2 years`
+fn age_label(edge: Duration) -> String {
+    let secs = edge.as_secs();
+    let (n, unit) = if secs.is_multiple_of(365 * DAY) {
+        (secs / (365 * DAY), "year")
+    } else if secs.is_multiple_of(30 * DAY) {
+        (secs / (30 * DAY), "month")
+    } else if secs.is_multiple_of(7 * DAY) {
+        (secs / (7 * DAY), "week")
+    } else if secs.is_multiple_of(DAY) {
+        (secs / DAY, "day")
+    } else {
+        (secs / (60 * 60), "hour")
+    };
+    format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" })
+}
+
+/// Folder counts per age range split at `edges` (sorted; duplicates and
+/// zero edges are ignored). Folders of unknown age aren't counted
+pub fn age_breakdown(folders: &[ClaudeFolder], edges: &[Duration]) -> Vec<AgeBucket> {
+    let mut edges: Vec<Duration> = edges.iter().copied().filter(|e| !e.is_zero()).collect();
+    edges.sort();
+    edges.dedup();
+
+    let mut buckets: Vec<AgeBucket> = edges
+        .iter()
+        .map(|&edge| AgeBucket {
+            label: format!("< {}", age_label(edge)),
+            max_age_secs: Some(edge.as_secs()),
+            count: 0,
+        })
+        .collect();
+    buckets.push(AgeBucket {
+        label: edges
+            .last()
+            .map_or_else(|| "Any age".to_string(), |&e| format!("> {}", age_label(e))),
+        max_age_secs: None,
+        count: 0,
+    });
+
+    let now = SystemTime::now();
+    for folder in folders {
+        if let Some(bucket) = age_bucket(folder, now, &edges) {
+            buckets[bucket].count += 1;
+        }
+    }
+    buckets
+}
+
+/// Bytes that would be freed by deleting everything older than each cutoff,
+/// oldest cutoff first
+pub fn reclaimable_by_age(folders: &[ClaudeFolder]) -> [(&'static str, u64); 3] {
+    let now = SystemTime::now();
+    let mut sizes = [0u64; 4];
+
+    for folder in folders {
+        if let Some(bucket) = age_bucket(folder, now, &DEFAULT_AGE_BUCKETS) {
+            sizes[bucket] += folder.size;
+        }
+    }
+
+    [
+        ("> 3 months", sizes[3]),
+        ("> 1 month", sizes[2] + sizes[3]),
+        ("> 1 week", sizes[1] + sizes[2] + sizes[3]),
+    ]
+}
+
+/// Summary of a single folder
+#[derive(Debug, Clone, Serialize)]
 pub struct FolderSummary {
     pub path: String,
     pub size: u64,
     pub size_human: String,
+    pub disk_size: u64,
+    pub disk_size_human: String,
+    pub apparent_size: u64,
+    pub file_count: u64,
+    pub project_type: String,
+    /// Scan root the folder was found under
+    pub root: Option<String>,
+}
+
+impl From<&ClaudeFolder> for FolderSummary {
+    fn from(f: &ClaudeFolder) -> Self {
+        Self {
+            path: f.path.to_string_lossy().to_string(),
+            size: f.size,
+            size_human: format_size(f.size),
+            disk_size: f.disk_size,
+            disk_size_human: format_size(f.disk_size),
+            apparent_size: f.apparent_size,
+            file_count: f.file_count,
+            project_type: f.project_type.clone(),
+            root: f.root.as_ref().map(|r| r.to_string_lossy().to_string()),
+        }
+    }
+}
+
+/// A single found folder, unaggregated (used by `--dry-run --json`)
+#[derive(Debug, Serialize)]
+pub struct FolderRecord {
+    pub path: String,
+    pub size: u64,
+    pub size_human: String,
+    pub file_count: u64,
     pub project_type: String,
+    pub modified_at: Option<DateTime<Utc>>,
+    /// Scan root the folder was found under
+    pub root: Option<String>,
+}
+
+impl From<&ClaudeFolder> for FolderRecord {
+    fn from(folder: &ClaudeFolder) -> Self {
+        Self {
+            path: folder.path.to_string_lossy().to_string(),
+            size: folder.size,
+            size_human: format_size(folder.size),
+            file_count: folder.file_count,
+            project_type: folder.project_type.clone(),
+            modified_at: folder.modified_at.map(DateTime::<Utc>::from),
+            root: folder
+                .root
+                .as_ref()
+                .map(|r| r.to_string_lossy().to_string()),
+        }
+    }
+}
+
+/// Serialize folders as a JSON array of `FolderRecord`s
+pub fn folders_to_json(folders: &[ClaudeFolder]) -> String {
+    let records: Vec<FolderRecord> = folders.iter().map(FolderRecord::from).collect();
+    serde_json::to_string_pretty(&records).unwrap_or_default()
+}
+
+/// Write each found folder that `keep` accepts to `out` as one JSON line
+/// (JSON Lines) as soon as it arrives, without collecting them first.
+/// Returns the paths that couldn't be read
+pub fn stream_folders(
+    rx: &Receiver<ScanEvent>,
+    out: &mut impl Write,
+    keep: impl Fn(&ClaudeFolder) -> bool,
+) -> io::Result<Vec<(PathBuf, String)>> {
+    let mut errors = Vec::new();
+    for event in rx {
+        match event {
+            ScanEvent::Found(folder) if keep(&folder) => {
+                serde_json::to_writer(&mut *out, &FolderRecord::from(&folder))?;
+                writeln!(out)?;
+                out.flush()?;
+            }
+            ScanEvent::Error(path, message) => errors.push((path, message)),
+            ScanEvent::Complete => break,
+            _ => {}
+        }
+    }
+    Ok(errors)
 }
 
 /// Complete space analysis report
@@ -38,16 +253,40 @@ pub struct SpaceReport {
     pub total_folders: usize,
     pub total_size: u64,
     pub total_size_human: String,
+    pub total_disk_size: u64,
+    pub total_disk_size_human: String,
+    pub total_apparent_size: u64,
+    pub total_apparent_size_human: String,
+    pub total_files: u64,
+    /// Biggest single file in any folder: (path, size)
+    pub largest_file: Option<(String, u64)>,
     pub by_project_type: HashMap<String, TypeStats>,
-    pub age_breakdown: AgeBreakdown,
+    /// Totals per scan root the folders were found under
+    pub by_scan_root: HashMap<String, TypeStats>,
+    /// Totals per directory below the scan roots (see `group_by_root_dir`)
+    pub by_root_dir: HashMap<String, TypeStats>,
+    /// Folder counts per age range, youngest first
+    pub age_breakdown: Vec<AgeBucket>,
     pub top_10_largest: Vec<FolderSummary>,
+    /// Every folder, largest first unless re-sorted with `sort_folders`
+    pub all_folders: Vec<FolderSummary>,
+    /// Paths skipped because they could not be read
+    pub skipped_paths: usize,
 }
 
 impl SpaceReport {
-    /// Generate report from folder list
-    pub fn generate(folders: &[ClaudeFolder]) -> Self {
+    /// Generate report from folder list, splitting ages at `age_buckets`
+    pub fn generate(folders: &[ClaudeFolder], age_buckets: &[Duration]) -> Self {
         let total_folders = folders.len();
         let total_size: u64 = folders.iter().map(|f| f.size).sum();
+        let total_disk_size: u64 = folders.iter().map(|f| f.disk_size).sum();
+        let total_apparent_size: u64 = folders.iter().map(|f| f.apparent_size).sum();
+        let total_files: u64 = folders.iter().map(|f| f.file_count).sum();
+        let largest_file = folders
+            .iter()
+            .filter_map(|f| f.largest_file.as_ref())
+            .max_by_key(|(_, size)| *size)
+            .map(|(path, size)| (path.to_string_lossy().to_string(), *size));
 
         // Group by project type
         let mut by_type: HashMap<String, Vec<&ClaudeFolder>> = HashMap::new();
@@ -60,72 +299,70 @@ impl SpaceReport {
 
         let by_project_type: HashMap<String, TypeStats> = by_type
             .into_iter()
-            .map(|(name, list)| {
-                let count = list.len();
-                let total: u64 = list.iter().map(|f| f.size).sum();
-                (
-                    name,
-                    TypeStats {
-                        count,
-                        total_size: total,
-                        avg_size: if count > 0 { total / count as u64 } else { 0 },
-                    },
-                )
-            })
+            .map(|(name, list)| (name, TypeStats::from_sizes(list.iter().map(|f| f.size))))
+            .collect();
+
+        // Group by scan root
+        let mut by_root: HashMap<String, Vec<u64>> = HashMap::new();
+        for folder in folders {
+            if let Some(root) = &folder.root {
+                by_root
+                    .entry(root.to_string_lossy().to_string())
+                    .or_default()
+                    .push(folder.size);
+            }
+        }
+        let by_scan_root: HashMap<String, TypeStats> = by_root
+            .into_iter()
+            .map(|(root, sizes)| (root, TypeStats::from_sizes(sizes.into_iter())))
             .collect();
 
         // Age breakdown
-        let age_breakdown = Self::calculate_age_breakdown(folders);
+        let age_breakdown = age_breakdown(folders, age_buckets);
 
-        // Top 10 largest
+        // All folders, largest first
         let mut sorted: Vec<_> = folders.iter().collect();
-        sorted.sort_by(|a, b| b.size.cmp(&a.size));
-        let top_10_largest: Vec<FolderSummary> = sorted
-            .into_iter()
-            .take(10)
-            .map(|f| FolderSummary {
-                path: f.path.to_string_lossy().to_string(),
-                size: f.size,
-                size_human: format_size(f.size),
-                project_type: f.project_type.clone(),
-            })
-            .collect();
+        sorted.sort_by_key(|f| std::cmp::Reverse(f.size));
+        let all_folders: Vec<FolderSummary> = sorted.into_iter().map(FolderSummary::from).collect();
+        let top_10_largest = all_folders.iter().take(10).cloned().collect();
 
         Self {
             total_folders,
             total_size,
             total_size_human: format_size(total_size),
+            total_disk_size,
+            total_disk_size_human: format_size(total_disk_size),
+            total_apparent_size,
+            total_apparent_size_human: format_size(total_apparent_size),
+            total_files,
+            largest_file,
             by_project_type,
+            by_scan_root,
+            by_root_dir: HashMap::new(),
             age_breakdown,
             top_10_largest,
+            all_folders,
+            skipped_paths: 0,
         }
     }
 
-    fn calculate_age_breakdown(folders: &[ClaudeFolder]) -> AgeBreakdown {
-        let now = SystemTime::now();
-        let week = Duration::from_secs(7 * 24 * 60 * 60);
-        let month = Duration::from_secs(30 * 24 * 60 * 60);
-        let quarter = Duration::from_secs(90 * 24 * 60 * 60);
-
-        let mut breakdown = AgeBreakdown::default();
+    /// List `all_folders` in another order (the top 10 stay the largest, so
+    /// only CSV and JSON output changes)
+    pub fn sort_folders(&mut self, folders: &[ClaudeFolder], order: SortOrder) {
+        let mut sorted: Vec<_> = folders.iter().collect();
+        sorted.sort_by(|a, b| order.compare(a, b));
+        self.all_folders = sorted.into_iter().map(FolderSummary::from).collect();
+    }
 
-        for folder in folders {
-            if let Some(modified) = folder.modified_at {
-                if let Ok(age) = now.duration_since(modified) {
-                    if age < week {
-                        breakdown.under_1_week += 1;
-                    } else if age < month {
-                        breakdown.under_1_month += 1;
-                    } else if age < quarter {
-                        breakdown.under_3_months += 1;
-                    } else {
-                        breakdown.over_3_months += 1;
-                    }
-                }
-            }
+    /// Render in the given export format, or `None` if the format is unknown
+    pub fn export(&self, format: &str) -> Option<String> {
+        match format {
+            "json" => Some(self.to_json()),
+            "csv" => Some(self.to_csv()),
+            "markdown" | "md" => Some(self.to_markdown()),
+            "html" => Some(self.to_html()),
+            _ => None,
         }
-
-        breakdown
     }
 
     /// Export to JSON
@@ -133,18 +370,287 @@ impl SpaceReport {
         serde_json::to_string_pretty(self).unwrap_or_default()
     }
 
-    /// Export to CSV (all folders, not just top 10)
+    /// Export to CSV (all folders, not just top 10). New columns go at the
+    /// end so scripts reading columns by position keep working
     pub fn to_csv(&self) -> String {
-        let mut csv = String::from("Path,Size (bytes),Size (human),Project Type\n");
-        for folder in &self.top_10_largest {
+        let mut csv = String::from("Path,Size (bytes),Size (human),Project Type,Files\n");
+        for folder in &self.all_folders {
             csv.push_str(&format!(
-                "\"{}\",{},{},{}\n",
-                folder.path, folder.size, folder.size_human, folder.project_type
+                "\"{}\",{},{},{},{}\n",
+                folder.path, folder.size, folder.size_human, folder.project_type, folder.file_count
             ));
         }
         csv
     }
 
+    /// Export to GitHub-flavored Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# ClaudeKill Space Analysis\n\n");
+
+        md.push_str("## Totals\n\n");
+        md.push_str(&format!("- **Folders:** {}\n", self.total_folders));
+        md.push_str(&format!("- **Total size:** {}\n", self.total_size_human));
+        md.push_str(&format!("- **Files:** {}\n", self.total_files));
+        if self.total_disk_size != self.total_size {
+            md.push_str(&format!("- **On disk:** {}\n", self.total_disk_size_human));
+        } else if self.total_apparent_size != self.total_size {
+            md.push_str(&format!(
+                "- **Apparent size:** {}\n",
+                self.total_apparent_size_human
+            ));
+        }
+        if self.skipped_paths > 0 {
+            md.push_str(&format!(
+                "- **Skipped:** {} paths (unreadable)\n",
+                self.skipped_paths
+            ));
+        }
+
+        md.push_str("\n## By Project Type\n\n");
+        md.push_str("| Project Type | Folders | Total Size | Average |\n");
+        md.push_str("| :--- | ---: | ---: | ---: |\n");
+        let mut types: Vec<_> = self.by_project_type.iter().collect();
+        types.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total_size));
+        for (name, stats) in types {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                markdown_escape(name),
+                stats.count,
+                format_size(stats.total_size),
+                format_size(stats.avg_size)
+            ));
+        }
+
+        md.push_str("\n## By Age\n\n");
+        md.push_str("| Age | Folders |\n");
+        md.push_str("| :--- | ---: |\n");
+        for bucket in &self.age_breakdown {
+            md.push_str(&format!("| {} | {} |\n", bucket.label, bucket.count));
+        }
+
+        if !self.top_10_largest.is_empty() {
+            md.push_str(&format!(
+                "\n## Top {} Largest\n\n",
+                self.top_10_largest.len()
+            ));
+            md.push_str("| # | Path | Size | Files | Project Type |\n");
+            md.push_str("| ---: | :--- | ---: | ---: | :--- |\n");
+            for (i, folder) in self.top_10_largest.iter().enumerate() {
+                md.push_str(&format!(
+                    "| {} | `{}` | {} | {} | {} |\n",
+                    i + 1,
+                    markdown_escape(&folder.path),
+                    folder.size_human,
+                    folder.file_count,
+                    markdown_escape(&folder.project_type)
+                ));
+            }
+        }
+
+        md
+    }
+
+    /// Fill `by_root_dir`, grouping folders `depth` levels below the scan
+    /// root they were found under (0 = one group per root)
+    pub fn group_by_root_dir(&mut self, roots: &[PathBuf], depth: usize) {
+        let mut groups: HashMap<String, Vec<u64>> = HashMap::new();
+        for folder in &self.all_folders {
+            let group = root_dir_group(Path::new(&folder.path), roots, depth);
+            groups
+                .entry(group.to_string_lossy().to_string())
+                .or_default()
+                .push(folder.size);
+        }
+        self.by_root_dir = groups
+            .into_iter()
+            .map(|(dir, sizes)| (dir, TypeStats::from_sizes(sizes.into_iter())))
+            .collect();
+    }
+
+    /// Export as a self-contained HTML page with a treemap of the largest folders
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>ClaudeKill Space Analysis</title>\n<style>\n\
+             body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }\n\
+             table { border-collapse: collapse; margin-bottom: 1.5em; }\n\
+             th, td { border: 1px solid #ccc; padding: 4px 10px; }\n\
+             td.num { text-align: right; }\n\
+             svg text { font-size: 12px; fill: #fff; pointer-events: none; }\n\
+             </style>\n</head>\n<body>\n<h1>ClaudeKill Space Analysis</h1>\n",
+        );
+
+        html.push_str("<h2>Totals</h2>\n<ul>\n");
+        html.push_str(&format!(
+            "<li><b>Folders:</b> {}</li>\n",
+            self.total_folders
+        ));
+        html.push_str(&format!(
+            "<li><b>Total size:</b> {}</li>\n",
+            self.total_size_human
+        ));
+        html.push_str(&format!("<li><b>Files:</b> {}</li>\n", self.total_files));
+        if self.total_disk_size != self.total_size {
+            html.push_str(&format!(
+                "<li><b>On disk:</b> {}</li>\n",
+                self.total_disk_size_human
+            ));
+        } else if self.total_apparent_size != self.total_size {
+            html.push_str(&format!(
+                "<li><b>Apparent size:</b> {}</li>\n",
+                self.total_apparent_size_human
+            ));
+        }
+        if self.skipped_paths > 0 {
+            html.push_str(&format!(
+                "<li><b>Skipped:</b> {} paths (unreadable)</li>\n",
+                self.skipped_paths
+            ));
+        }
+        html.push_str("</ul>\n");
+
+        if !self.top_10_largest.is_empty() {
+            html.push_str(&format!(
+                "<h2>Top {} Largest</h2>\n",
+                self.top_10_largest.len()
+            ));
+            html.push_str(&self.treemap_svg(800.0, 400.0));
+        }
+
+        html.push_str("<h2>By Project Type</h2>\n<table>\n");
+        html.push_str(
+            "<tr><th>Project Type</th><th>Folders</th><th>Total Size</th><th>Average</th></tr>\n",
+        );
+        let mut types: Vec<_> = self.by_project_type.iter().collect();
+        types.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total_size));
+        for (name, stats) in types {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                html_escape(name),
+                stats.count,
+                format_size(stats.total_size),
+                format_size(stats.avg_size)
+            ));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>By Age</h2>\n<table>\n<tr><th>Age</th><th>Folders</th></tr>\n");
+        for bucket in &self.age_breakdown {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td></tr>\n",
+                html_escape(&bucket.label),
+                bucket.count
+            ));
+        }
+        html.push_str("</table>\n");
+
+        if !self.top_10_largest.is_empty() {
+            html.push_str(
+                "<table>\n<tr><th>#</th><th>Path</th><th>Size</th><th>Files</th><th>Project Type</th></tr>\n",
+            );
+            for (i, folder) in self.top_10_largest.iter().enumerate() {
+                html.push_str(&format!(
+                    "<tr><td class=\"num\">{}</td><td><code>{}</code></td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                    i + 1,
+                    html_escape(&folder.path),
+                    folder.size_human,
+                    folder.file_count,
+                    html_escape(&folder.project_type)
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// SVG treemap of the largest folders, hover for full path and size
+    fn treemap_svg(&self, width: f64, height: f64) -> String {
+        let sizes: Vec<u64> = self.top_10_largest.iter().map(|f| f.size).collect();
+        let bounds = treemap::Rect {
+            x: 0.0,
+            y: 0.0,
+            w: width,
+            h: height,
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = width,
+            h = height
+        );
+        let rects = treemap::squarify(&sizes, bounds);
+        for (i, (rect, folder)) in rects.iter().zip(&self.top_10_largest).enumerate() {
+            if rect.w * rect.h <= 0.0 {
+                continue;
+            }
+            // Spread hues evenly so neighbours stay distinguishable
+            let hue = (i * 360 / self.top_10_largest.len().max(1) + 200) % 360;
+            svg.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"hsl({}, 55%, 45%)\" stroke=\"#fff\"><title>{} ({})</title></rect>\n",
+                rect.x,
+                rect.y,
+                rect.w,
+                rect.h,
+                hue,
+                html_escape(&folder.path),
+                folder.size_human
+            ));
+            // Only label boxes with room for the text
+            if rect.w >= 80.0 && rect.h >= 36.0 {
+                let name = std::path::Path::new(&folder.path)
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| folder.path.clone());
+                svg.push_str(&format!(
+                    "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n",
+                    rect.x + 6.0,
+                    rect.y + 16.0,
+                    html_escape(&name),
+                    rect.x + 6.0,
+                    rect.y + 30.0,
+                    folder.size_human
+                ));
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Print counts only, for scans that skipped size calculation
+    pub fn print_counts(&self) {
+        println!();
+        println!("=== ClaudeKill Folder Count ===");
+        println!();
+        println!("Total Folders: {}", self.total_folders);
+        if self.skipped_paths > 0 {
+            println!("Skipped:       {} paths (unreadable)", self.skipped_paths);
+        }
+        println!();
+
+        println!("By Project Type:");
+        println!("{:-<60}", "");
+        let mut types: Vec<_> = self.by_project_type.iter().collect();
+        types.sort_by(|(a_name, a), (b_name, b)| b.count.cmp(&a.count).then(a_name.cmp(b_name)));
+        for (name, stats) in types {
+            println!("  {:15} {:>4} folders", name, stats.count);
+        }
+        println!();
+
+        println!("By Age:");
+        println!("{:-<60}", "");
+        for bucket in &self.age_breakdown {
+            println!(
+                "  {:12} {:>4} folders",
+                format!("{}:", bucket.label),
+                bucket.count
+            );
+        }
+        println!();
+    }
+
     /// Print human-readable summary to stdout
     pub fn print_summary(&self) {
         println!();
@@ -152,12 +658,44 @@ impl SpaceReport {
         println!();
         println!("Total Folders: {}", self.total_folders);
         println!("Total Size:    {}", self.total_size_human);
+        println!("Total Files:   {}", self.total_files);
+        if let Some((path, size)) = &self.largest_file {
+            println!("Biggest single file: {} ({})", format_size(*size), path);
+        }
+        if self.total_disk_size != self.total_size {
+            println!("On Disk:       {}", self.total_disk_size_human);
+        } else if self.total_apparent_size != self.total_size {
+            println!("Apparent Size: {}", self.total_apparent_size_human);
+        }
+        if self.skipped_paths > 0 {
+            println!(
+                "Skipped:       {} paths (unreadable); sizes may be under-counted",
+                self.skipped_paths
+            );
+        }
         println!();
 
+        if !self.by_scan_root.is_empty() {
+            println!("By Scan Root:");
+            println!("{:-<60}", "");
+            let mut roots: Vec<_> = self.by_scan_root.iter().collect();
+            roots.sort_by_key(|(root, _)| *root);
+            for (root, stats) in roots {
+                let root = crate::utils::truncate_start(root, 30);
+                println!(
+                    "  {:30} {:>4} folders  {:>10}",
+                    root,
+                    stats.count,
+                    format_size(stats.total_size)
+                );
+            }
+            println!();
+        }
+
         println!("By Project Type:");
         println!("{:-<60}", "");
         let mut types: Vec<_> = self.by_project_type.iter().collect();
-        types.sort_by(|a, b| b.1.total_size.cmp(&a.1.total_size));
+        types.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total_size));
         for (name, stats) in types {
             println!(
                 "  {:15} {:>4} folders  {:>10}  (avg: {})",
@@ -169,42 +707,163 @@ impl SpaceReport {
         }
         println!();
 
+        if !self.by_root_dir.is_empty() {
+            println!("By Directory:");
+            println!("{:-<60}", "");
+            let mut dirs: Vec<_> = self.by_root_dir.iter().collect();
+            dirs.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total_size));
+            for (dir, stats) in dirs {
+                let dir = crate::utils::truncate_start(dir, 30);
+                println!(
+                    "  {:30} {:>4} folders  {:>10}",
+                    dir,
+                    stats.count,
+                    format_size(stats.total_size)
+                );
+            }
+            println!();
+        }
+
         println!("By Age:");
         println!("{:-<60}", "");
-        println!(
-            "  < 1 week:    {:>4} folders",
-            self.age_breakdown.under_1_week
-        );
-        println!(
-            "  < 1 month:   {:>4} folders",
-            self.age_breakdown.under_1_month
-        );
-        println!(
-            "  < 3 months:  {:>4} folders",
-            self.age_breakdown.under_3_months
-        );
-        println!(
-            "  > 3 months:  {:>4} folders",
-            self.age_breakdown.over_3_months
-        );
+        for bucket in &self.age_breakdown {
+            println!(
+                "  {:12} {:>4} folders",
+                format!("{}:", bucket.label),
+                bucket.count
+            );
+        }
         println!();
 
         if !self.top_10_largest.is_empty() {
             println!("Top {} Largest:", self.top_10_largest.len());
             println!("{:-<60}", "");
             for (i, folder) in self.top_10_largest.iter().enumerate() {
-                let path = if folder.path.len() > 45 {
-                    format!("...{}", &folder.path[folder.path.len() - 42..])
+                let path = crate::utils::truncate_start(&folder.path, 45);
+                if folder.disk_size != folder.size {
+                    println!(
+                        "  {:>2}. {:>10}  {}  ({} on disk)",
+                        i + 1,
+                        folder.size_human,
+                        path,
+                        folder.disk_size_human
+                    );
+                } else if folder.apparent_size != folder.size {
+                    println!(
+                        "  {:>2}. {:>10}  {}  ({} apparent)",
+                        i + 1,
+                        folder.size_human,
+                        path,
+                        format_size(folder.apparent_size)
+                    );
                 } else {
-                    folder.path.clone()
-                };
-                println!("  {:>2}. {:>10}  {}", i + 1, folder.size_human, path);
+                    println!("  {:>2}. {:>10}  {}", i + 1, folder.size_human, path);
+                }
             }
             println!();
         }
     }
 }
 
+/// Export format implied by a file extension (`report.csv` -> "csv")
+pub fn format_for_path(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+    match ext.as_str() {
+        "json" => Some("json"),
+        "csv" => Some("csv"),
+        "md" | "markdown" => Some("markdown"),
+        "html" | "htm" => Some("html"),
+        _ => None,
+    }
+}
+
+/// Whether `format` is accepted by `SpaceReport::export`
+pub fn is_export_format(format: &str) -> bool {
+    matches!(format, "json" | "csv" | "markdown" | "md" | "html")
+}
+
+/// Escape text for HTML element content and attribute values
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Escape pipes so a value can't break a Markdown table row
+fn markdown_escape(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Periodically writes the partial report of a long scan to disk
+pub struct Checkpoint {
+    path: PathBuf,
+    format: String,
+    interval: Duration,
+    last_write: Instant,
+    age_buckets: Vec<Duration>,
+}
+
+impl Checkpoint {
+    pub fn new(path: PathBuf, format: &str, interval: Duration) -> Self {
+        Self {
+            path,
+            format: format.to_string(),
+            interval,
+            last_write: Instant::now(),
+            age_buckets: DEFAULT_AGE_BUCKETS.to_vec(),
+        }
+    }
+
+    pub fn with_age_buckets(mut self, age_buckets: Vec<Duration>) -> Self {
+        self.age_buckets = age_buckets;
+        self
+    }
+
+    /// Write a report of the folders found so far if the interval has elapsed
+    pub fn maybe_write(&mut self, folders: &[ClaudeFolder]) -> Result<bool> {
+        if self.last_write.elapsed() < self.interval {
+            return Ok(false);
+        }
+        let content = SpaceReport::generate(folders, &self.age_buckets)
+            .export(&self.format)
+            .unwrap_or_default();
+        crate::utils::write_atomic(&self.path, &content)
+            .with_context(|| format!("Failed to write checkpoint: {}", self.path.display()))?;
+        self.last_write = Instant::now();
+        Ok(true)
+    }
+}
+
+/// Collect scan results until the scan completes, writing checkpoints as it goes
+pub fn collect_folders(
+    rx: &Receiver<ScanEvent>,
+    mut checkpoint: Option<&mut Checkpoint>,
+) -> Result<ScanResults> {
+    let mut results = ScanResults::default();
+    // Wake up periodically even when no events arrive so checkpoints stay on schedule
+    let tick = checkpoint
+        .as_ref()
+        .map(|c| c.interval)
+        .unwrap_or(Duration::from_secs(3600));
+
+    loop {
+        match rx.recv_timeout(tick) {
+            Ok(ScanEvent::Found(folder)) => results.folders.push(folder),
+            Ok(ScanEvent::Error(path, message)) => results.errors.push((path, message)),
+            Ok(ScanEvent::Complete) | Err(RecvTimeoutError::Disconnected) => break,
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+        }
+        if let Some(c) = checkpoint.as_deref_mut() {
+            c.maybe_write(&results.folders)?;
+        }
+    }
+
+    Ok(results)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,19 +876,110 @@ mod tests {
             project_type: project_type.to_string(),
             selected: false,
             modified_at: Some(SystemTime::now()),
+            ..Default::default()
         }
     }
 
     #[test]
-    fn test_generate_report() {
+    fn test_reclaimable_by_age() {
+        let days = |n: u64| SystemTime::now() - Duration::from_secs(n * 24 * 60 * 60);
+        let mut folders = vec![
+            make_folder("/new/.claude", 1, "Rust"),
+            make_folder("/week/.claude", 10, "Rust"),
+            make_folder("/month/.claude", 100, "Rust"),
+            make_folder("/old/.claude", 1000, "Rust"),
+            make_folder("/unknown/.claude", 10000, "Rust"),
+        ];
+        folders[1].modified_at = Some(days(10));
+        folders[2].modified_at = Some(days(45));
+        folders[3].modified_at = Some(days(200));
+        folders[4].modified_at = None;
+
+        assert_eq!(
+            reclaimable_by_age(&folders),
+            [
+                ("> 3 months", 1000),
+                ("> 1 month", 1100),
+                ("> 1 week", 1110)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_age_buckets() {
+        let days = |n: u64| SystemTime::now() - Duration::from_secs(n * DAY);
+        let mut folders: Vec<_> = [10, 100, 200, 400]
+            .iter()
+            .map(|_| make_folder("/p/.claude", 1, "Rust"))
+            .collect();
+        for (folder, age) in folders.iter_mut().zip([10, 100, 200, 400]) {
+            folder.modified_at = Some(days(age));
+        }
+
+        let edges = [
+            Duration::from_secs(365 * DAY),
+            Duration::from_secs(90 * DAY),
+            Duration::from_secs(180 * DAY),
+        ];
+        let buckets = age_breakdown(&folders, &edges);
+        let summary: Vec<_> = buckets
+            .iter()
+            .map(|b| (b.label.as_str(), b.count))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("< 3 months", 1),
+                ("< 6 months", 1),
+                ("< 1 year", 1),
+                ("> 1 year", 1)
+            ]
+        );
+
+        // The defaults keep the original labels
+        let report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
+        let labels: Vec<_> = report
+            .age_breakdown
+            .iter()
+            .map(|b| b.label.as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            ["< 1 week", "< 1 month", "< 3 months", "> 3 months"]
+        );
+    }
+
+    #[test]
+    fn test_sort_folders() {
         let folders = vec![
+            make_folder("/b/.claude", 1, "Rust"),
+            make_folder("/c/.claude", 100, "Rust"),
+            make_folder("/a/.claude", 10, "Rust"),
+        ];
+        let mut report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
+        report.sort_folders(&folders, SortOrder::NameAsc);
+
+        let paths: Vec<_> = report.all_folders.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["/a/.claude", "/b/.claude", "/c/.claude"]);
+        assert_eq!(report.top_10_largest[0].path, "/c/.claude");
+    }
+
+    #[test]
+    fn test_generate_report() {
+        let mut folders = vec![
             make_folder("/a/.claude", 1000, "Rust"),
             make_folder("/b/.claude", 2000, "Rust"),
             make_folder("/c/.claude", 500, "Node"),
         ];
+        folders[1].largest_file = Some((PathBuf::from("/b/.claude/cache.db"), 1500));
+        folders[2].largest_file = Some((PathBuf::from("/c/.claude/log"), 400));
 
-        let report = SpaceReport::generate(&folders);
+        let report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
 
+        assert_eq!(
+            report.largest_file,
+            Some(("/b/.claude/cache.db".to_string(), 1500))
+        );
         assert_eq!(report.total_folders, 3);
         assert_eq!(report.total_size, 3500);
         assert_eq!(report.by_project_type.len(), 2);
@@ -239,7 +989,7 @@ mod tests {
     #[test]
     fn test_to_json() {
         let folders = vec![make_folder("/test/.claude", 1000, "Unknown")];
-        let report = SpaceReport::generate(&folders);
+        let report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
         let json = report.to_json();
 
         assert!(json.contains("\"total_folders\": 1"));
@@ -248,11 +998,197 @@ mod tests {
 
     #[test]
     fn test_to_csv() {
-        let folders = vec![make_folder("/test/.claude", 1000, "Unknown")];
-        let report = SpaceReport::generate(&folders);
+        let mut folders = vec![make_folder("/test/.claude", 1000, "Unknown")];
+        folders[0].file_count = 42;
+        let report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
+        let csv = report.to_csv();
+
+        assert!(csv.starts_with("Path,Size (bytes),Size (human),Project Type,Files\n"));
+        assert!(csv.contains("\"/test/.claude\",1000,1000 B,Unknown,42"));
+        assert_eq!(report.total_files, 42);
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let folders = vec![
+            make_folder("/a/.claude", 2048, "Rust"),
+            make_folder("/b|c/.claude", 1024, "Node"),
+        ];
+        let report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
+        let md = report.to_markdown();
+
+        assert!(md.starts_with("# ClaudeKill Space Analysis\n"));
+        assert!(md.contains("| # | Path | Size | Files | Project Type |"));
+        assert!(md.contains("- **Total size:** 3.0 KB"));
+        assert!(md.contains("| Rust | 1 | 2.0 KB | 2.0 KB |"));
+        assert!(md.contains("`/b\\|c/.claude`"));
+    }
+
+    #[test]
+    fn test_format_for_path() {
+        assert_eq!(format_for_path(Path::new("out/report.CSV")), Some("csv"));
+        assert_eq!(format_for_path(Path::new("report.md")), Some("markdown"));
+        assert_eq!(format_for_path(Path::new("report.htm")), Some("html"));
+        assert_eq!(format_for_path(Path::new("report.txt")), None);
+        assert_eq!(format_for_path(Path::new("report")), None);
+    }
+
+    #[test]
+    fn test_by_scan_root() {
+        let mut folders = vec![
+            make_folder("/w/a/.claude", 100, "Rust"),
+            make_folder("/w/b/.claude", 50, "Rust"),
+            make_folder("/p/c/.claude", 10, "Node"),
+        ];
+        folders[0].root = Some(PathBuf::from("/w"));
+        folders[1].root = Some(PathBuf::from("/w"));
+        folders[2].root = Some(PathBuf::from("/p"));
+
+        let report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
+        let work = &report.by_scan_root["/w"];
+        assert_eq!((work.count, work.total_size), (2, 150));
+        assert_eq!(report.by_scan_root["/p"].count, 1);
+
+        let json = report.to_json();
+        assert!(json.contains("\"by_scan_root\""));
+        assert!(json.contains("\"root\": \"/w\""));
+        assert!(folders_to_json(&folders).contains("\"root\": \"/p\""));
+    }
+
+    #[test]
+    fn test_group_by_root_dir() {
+        let folders = vec![
+            make_folder("/home/u/work/api/.claude", 100, "Rust"),
+            make_folder("/home/u/work/web/app/.claude", 50, "Node"),
+            make_folder("/home/u/personal/blog/.claude", 10, "Node"),
+            make_folder("/home/u/.claude", 5, "Unknown"),
+            make_folder("/srv/x/.claude", 1, "Unknown"),
+        ];
+        let roots = vec![PathBuf::from("/home/u"), PathBuf::from("/srv")];
+        let mut report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
+
+        report.group_by_root_dir(&roots, 1);
+        let work = &report.by_root_dir["/home/u/work"];
+        assert_eq!((work.count, work.total_size), (2, 150));
+        assert_eq!(report.by_root_dir["/home/u/personal"].count, 1);
+        // A folder directly in the root groups under the root itself
+        assert_eq!(report.by_root_dir["/home/u"].total_size, 5);
+        assert_eq!(report.by_root_dir["/srv/x"].count, 1);
+
+        report.group_by_root_dir(&roots, 0);
+        assert_eq!(report.by_root_dir.len(), 2);
+        assert_eq!(report.by_root_dir["/home/u"].total_size, 165);
+
+        report.group_by_root_dir(&roots, 2);
+        assert!(report.by_root_dir.contains_key("/home/u/work/web"));
+        assert!(report.to_json().contains("\"by_root_dir\""));
+    }
+
+    #[test]
+    fn test_to_html() {
+        let folders = vec![
+            make_folder("/a/web/.claude", 2048, "Rust"),
+            make_folder("/b/<x>/.claude", 1024, "Node"),
+        ];
+        let report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
+        let html = report.to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.ends_with("</html>\n"));
+        assert_eq!(html.matches("<rect ").count(), 2);
+        assert!(html.contains("<title>/a/web/.claude (2.0 KB)</title>"));
+        assert!(html.contains("/b/&lt;x&gt;/.claude"));
+        assert!(!html.contains("<x>"));
+        // Self-contained: no external scripts or stylesheets
+        assert!(!html.contains("<script") && !html.contains("<link"));
+    }
+
+    #[test]
+    fn test_folders_to_json() {
+        let folders = vec![
+            make_folder("/a/.claude", 2048, "Rust"),
+            make_folder("/b/.claude", 10, "Go"),
+        ];
+        let value: serde_json::Value = serde_json::from_str(&folders_to_json(&folders)).unwrap();
+        let records = value.as_array().unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["path"], "/a/.claude");
+        assert_eq!(records[0]["size"], 2048);
+        assert_eq!(records[0]["size_human"], "2.0 KB");
+        assert_eq!(records[0]["project_type"], "Rust");
+        assert!(records[0]["modified_at"].is_string());
+    }
+
+    #[test]
+    fn test_to_csv_includes_all_folders() {
+        let folders: Vec<_> = (0..15)
+            .map(|i| make_folder(&format!("/p{}/.claude", i), 1000 + i, "Rust"))
+            .collect();
+        let report = SpaceReport::generate(&folders, &DEFAULT_AGE_BUCKETS);
         let csv = report.to_csv();
 
-        assert!(csv.starts_with("Path,Size (bytes),Size (human),Project Type\n"));
-        assert!(csv.contains("/test/.claude"));
+        assert_eq!(report.top_10_largest.len(), 10);
+        assert_eq!(csv.lines().count() - 1, 15);
+    }
+
+    #[test]
+    fn test_stream_folders_jsonl() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(ScanEvent::Found(make_folder("/a/.claude", 1000, "Rust")))
+            .unwrap();
+        tx.send(ScanEvent::Found(make_folder("/b/.claude", 10, "Node")))
+            .unwrap();
+        tx.send(ScanEvent::Error(PathBuf::from("/locked"), "denied".into()))
+            .unwrap();
+        tx.send(ScanEvent::Complete).unwrap();
+
+        let mut out = Vec::new();
+        let errors = stream_folders(&rx, &mut out, |f| f.size > 100).unwrap();
+        assert_eq!(errors.len(), 1);
+
+        let lines: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
+        assert_eq!(lines.len(), 1);
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["path"], "/a/.claude");
+        assert_eq!(record["size"], 1000);
+    }
+
+    #[test]
+    fn test_checkpoint_waits_for_interval() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("report.json");
+        let interval = Duration::from_secs(60);
+        let mut checkpoint = Checkpoint::new(path.clone(), "json", interval);
+        let folders = vec![make_folder("/a/.claude", 1000, "Rust")];
+
+        assert!(!checkpoint.maybe_write(&folders).unwrap());
+        assert!(!path.exists());
+
+        // Pretend the interval has passed instead of sleeping through it
+        checkpoint.last_write -= interval;
+        assert!(checkpoint.maybe_write(&folders).unwrap());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("/a/.claude"));
+        assert!(!checkpoint.maybe_write(&folders).unwrap());
+    }
+
+    #[test]
+    fn test_checkpoint_written_during_scan() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("report.json");
+        let mut checkpoint = Checkpoint::new(path.clone(), "json", Duration::ZERO);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(ScanEvent::Found(make_folder("/a/.claude", 1000, "Rust")))
+            .unwrap();
+        tx.send(ScanEvent::Complete).unwrap();
+
+        let results = collect_folders(&rx, Some(&mut checkpoint)).unwrap();
+        assert_eq!(results.folders.len(), 1);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("/a/.claude"));
     }
 }
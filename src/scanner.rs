@@ -1,20 +1,38 @@
 // Scanner module - finds .claude folders recursively using parallel walking
 
 use crate::project;
-use jwalk::WalkDir;
+use crate::state::{CachedSize, SizeCache};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use jwalk::{WalkDir, WalkDirGeneric};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::SystemTime;
 
 /// Represents a found .claude folder with metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ClaudeFolder {
     pub path: PathBuf,
+    /// Size used for totals, sorting and filters: apparent size, or the
+    /// on-disk size under block accounting
     pub size: u64,
+    /// Space actually allocated on disk (differs for sparse/compressed files)
+    pub disk_size: u64,
+    /// Sum of logical file lengths
+    pub apparent_size: u64,
+    /// Number of files inside (directories not counted)
+    pub file_count: u64,
+    /// Biggest file inside and its apparent size
+    pub largest_file: Option<(PathBuf, u64)>,
     pub project_type: String,
     pub selected: bool,
     pub modified_at: Option<SystemTime>,
+    /// Scan root the folder was found under
+    pub root: Option<PathBuf>,
 }
 
 impl ClaudeFolder {
@@ -22,6 +40,17 @@ impl ClaudeFolder {
     pub fn size_display(&self) -> String {
         crate::utils::format_size(self.size)
     }
+
+    /// The figure `size` doesn't show (on-disk or apparent), when it differs
+    pub fn other_size(&self) -> Option<u64> {
+        if self.disk_size != self.size {
+            Some(self.disk_size)
+        } else if self.apparent_size != self.size {
+            Some(self.apparent_size)
+        } else {
+            None
+        }
+    }
 }
 
 /// Events emitted during scanning
@@ -29,127 +58,1088 @@ impl ClaudeFolder {
 pub enum ScanEvent {
     Scanning(PathBuf),
     Found(ClaudeFolder),
+    /// A path could not be read (e.g. permission denied) and was skipped
+    Error(PathBuf, String),
     Complete,
 }
 
+/// Everything collected from a finished scan
+#[derive(Debug, Default)]
+pub struct ScanResults {
+    pub folders: Vec<ClaudeFolder>,
+    /// Paths that could not be read, with the reason
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+/// Handle to a scan running on a background thread
+pub struct ScanHandle {
+    pub rx: Receiver<ScanEvent>,
+    cancelled: Arc<AtomicBool>,
+    dirs_walked: Arc<AtomicUsize>,
+}
+
+impl ScanHandle {
+    /// Ask the background scan to stop as soon as possible
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Directories walked so far (final once `ScanEvent::Complete` arrives)
+    pub fn dirs_walked(&self) -> usize {
+        self.dirs_walked.load(Ordering::Relaxed)
+    }
+}
+
 /// Scanner for finding .claude folders with parallel directory walking
+#[derive(Clone)]
 pub struct Scanner {
-    root: PathBuf,
+    roots: Vec<PathBuf>,
     include_global: bool,
-    exclude_patterns: Vec<String>,
+    exclude: ExcludeMatcher,
+    max_depth: Option<usize>,
+    min_size: Option<u64>,
+    custom_markers: Vec<(String, String)>,
+    max_ancestors: usize,
+    follow_symlinks: bool,
+    respect_gitignore: bool,
+    size_cache: Option<PathBuf>,
+    skip_sizes: bool,
+    disk_usage: bool,
+    include_files: bool,
+    jobs: Option<usize>,
+    /// Absolute directories never walked into (e.g. the quarantine directory)
+    skip_dirs: Vec<PathBuf>,
 }
 
+/// Compiled exclude patterns
+///
+/// Patterns containing glob metacharacters (`*?[{`) are globs matched against
+/// the full path; relative globs match at any depth (`target/**` is treated
+/// as `**/target/**`) and `*` doesn't cross `/`. Other patterns keep the old
+/// behaviour of excluding any path that contains them.
+#[derive(Clone, Default)]
+struct ExcludeMatcher {
+    globs: GlobSet,
+    substrings: Vec<String>,
+}
+
+impl ExcludeMatcher {
+    fn new(patterns: &[String]) -> Self {
+        let mut globs = GlobSetBuilder::new();
+        let mut substrings = Vec::new();
+
+        for pattern in patterns {
+            if !pattern.contains(['*', '?', '[', '{']) {
+                substrings.push(pattern.clone());
+                continue;
+            }
+            let anchored = if pattern.starts_with('/') || pattern.starts_with("**") {
+                pattern.clone()
+            } else {
+                format!("**/{}", pattern)
+            };
+            match GlobBuilder::new(&anchored).literal_separator(true).build() {
+                Ok(glob) => {
+                    globs.add(glob);
+                }
+                // Not a valid glob: fall back to a plain substring match
+                Err(_) => substrings.push(pattern.clone()),
+            }
+        }
+
+        Self {
+            globs: globs.build().unwrap_or_default(),
+            substrings,
+        }
+    }
+
+    fn is_match(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        self.substrings
+            .iter()
+            .any(|s| path_str.contains(s.as_str()))
+            || self.globs.is_match(path)
+    }
+}
+
+/// `.gitignore` matchers in effect for a directory, outermost first
+type GitignoreStack = Vec<Arc<Gitignore>>;
+
 impl Scanner {
-    pub fn new(root: PathBuf, include_global: bool, exclude_patterns: Vec<String>) -> Self {
+    pub fn new(roots: Vec<PathBuf>, include_global: bool, exclude_patterns: Vec<String>) -> Self {
         Self {
-            root,
+            roots,
             include_global,
-            exclude_patterns,
+            exclude: ExcludeMatcher::new(&exclude_patterns),
+            max_depth: None,
+            min_size: None,
+            custom_markers: Vec::new(),
+            max_ancestors: project::DEFAULT_MAX_ANCESTORS,
+            follow_symlinks: false,
+            respect_gitignore: false,
+            size_cache: None,
+            skip_sizes: false,
+            disk_usage: false,
+            include_files: false,
+            jobs: None,
+            skip_dirs: Vec::new(),
         }
     }
 
-    /// Scan for .claude folders, returns receiver for streaming results
-    pub fn scan(&self) -> Receiver<ScanEvent> {
+    /// Limit recursion depth (0 = only the root itself)
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Drop folders smaller than `min_size` bytes
+    pub fn with_min_size(mut self, min_size: Option<u64>) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Marker-file-to-label mappings checked before built-in project detection
+    pub fn with_custom_markers(mut self, custom_markers: Vec<(String, String)>) -> Self {
+        self.custom_markers = custom_markers;
+        self
+    }
+
+    /// How many directories above a folder to search for project markers
+    pub fn with_max_ancestors(mut self, max_ancestors: usize) -> Self {
+        self.max_ancestors = max_ancestors;
+        self
+    }
+
+    /// Traverse symlinked directories instead of just reporting them
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Prune directories ignored by `.gitignore` files found along the walk
+    pub fn with_respect_gitignore(mut self, respect_gitignore: bool) -> Self {
+        self.respect_gitignore = respect_gitignore;
+        self
+    }
+
+    /// Reuse folder sizes from a previous run, stored at `cache_path`
+    pub fn with_size_cache(mut self, cache_path: Option<PathBuf>) -> Self {
+        self.size_cache = cache_path;
+        self
+    }
+
+    /// Only find folders; report every size as 0 instead of walking them
+    pub fn with_skip_sizes(mut self, skip_sizes: bool) -> Self {
+        self.skip_sizes = skip_sizes;
+        self
+    }
+
+    /// Count allocated blocks instead of file lengths, so totals match what
+    /// `df` reports as freed (Unix only; elsewhere both figures are equal)
+    pub fn with_disk_usage(mut self, disk_usage: bool) -> Self {
+        self.disk_usage = disk_usage;
+        self
+    }
+
+    /// Also report `.claude` regular files, as folders of type "File"
+    pub fn with_include_files(mut self, include_files: bool) -> Self {
+        self.include_files = include_files;
+        self
+    }
+
+    /// Don't walk into these directories, so folders already moved to the
+    /// quarantine directory aren't found again
+    pub fn with_skipped_dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.skip_dirs = dirs
+            .iter()
+            .filter_map(|dir| std::path::absolute(dir).ok())
+            .collect();
+        self
+    }
+
+    /// Walk and size with this many threads (1 = sequential; unset = one
+    /// per CPU)
+    pub fn with_jobs(mut self, jobs: Option<usize>) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// Whether stray `.claude` files are reported too
+    pub fn include_files(&self) -> bool {
+        self.include_files
+    }
+
+    /// Root directories being scanned
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Roots formatted for display (e.g. "~/work, ~/personal")
+    pub fn roots_display(&self) -> String {
+        self.roots
+            .iter()
+            .map(|r| r.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Scan for .claude folders, returns a handle for streaming results
+    pub fn scan(&self) -> ScanHandle {
+        self.scan_with_flag(Arc::new(AtomicBool::new(false)))
+    }
+
+    fn scan_with_flag(&self, cancelled: Arc<AtomicBool>) -> ScanHandle {
         let (tx, rx) = channel();
-        let root = self.root.clone();
-        let include_global = self.include_global;
-        let exclude_patterns = self.exclude_patterns.clone();
+        let scanner = self.clone();
         let global_path = dirs::home_dir().map(|h| h.join(".claude"));
+        let flag = Arc::clone(&cancelled);
+        let dirs_walked = Arc::new(AtomicUsize::new(0));
+        let walked = Arc::clone(&dirs_walked);
 
         thread::spawn(move || {
-            Self::scan_dir(
-                &root,
-                &tx,
-                include_global,
-                global_path.as_deref(),
-                &exclude_patterns,
+            let cache = Mutex::new(
+                scanner
+                    .size_cache
+                    .as_deref()
+                    .map(SizeCache::load_from)
+                    .unwrap_or_default(),
             );
+
+            // Folders are sized on worker threads while the walk continues
+            let (work_tx, work_rx) = channel::<(PathBuf, PathBuf)>();
+            let work_rx = Mutex::new(work_rx);
+            let workers = scanner
+                .jobs
+                .unwrap_or_else(|| thread::available_parallelism().map_or(4, |n| n.get()));
+
+            thread::scope(|s| {
+                for _ in 0..workers {
+                    s.spawn(|| loop {
+                        let next = work_rx.lock().unwrap().recv();
+                        let Ok((path, root)) = next else { break };
+                        if !flag.load(Ordering::Relaxed) {
+                            scanner.measure(path, root, &tx, &cache);
+                        }
+                    });
+                }
+
+                // Overlapping roots can reach the same folder more than once
+                let mut seen = HashSet::new();
+                for root in &scanner.roots {
+                    if flag.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let dirs = scanner.scan_dir(
+                        root,
+                        &tx,
+                        &work_tx,
+                        global_path.as_deref(),
+                        &mut seen,
+                        &flag,
+                    );
+                    walked.fetch_add(dirs, Ordering::Relaxed);
+                }
+                drop(work_tx);
+            });
+
+            if let Some(path) = &scanner.size_cache {
+                let _ = cache.into_inner().unwrap().save_to(path);
+            }
             let _ = tx.send(ScanEvent::Complete);
         });
 
-        rx
+        ScanHandle {
+            rx,
+            cancelled,
+            dirs_walked,
+        }
     }
 
     /// Check if a path should be excluded based on patterns
-    fn should_exclude(path: &Path, patterns: &[String]) -> bool {
-        let path_str = path.to_string_lossy();
-        patterns.iter().any(|pattern| path_str.contains(pattern))
+    fn should_exclude(&self, path: &Path) -> bool {
+        self.exclude.is_match(path)
     }
 
+    /// Walk one root, handing found folders to the sizing workers.
+    /// Returns how many directories were walked
     fn scan_dir(
+        &self,
         root: &Path,
         tx: &Sender<ScanEvent>,
-        include_global: bool,
+        work_tx: &Sender<(PathBuf, PathBuf)>,
         global_path: Option<&Path>,
-        exclude_patterns: &[String],
-    ) {
+        seen: &mut HashSet<PathBuf>,
+        cancelled: &AtomicBool,
+    ) -> usize {
+        let mut dirs_walked = 0;
+        let mut walker = WalkDirGeneric::<(GitignoreStack, ())>::new(root)
+            .skip_hidden(false)
+            .follow_links(self.follow_symlinks);
+        if let Some(depth) = self.max_depth {
+            walker = walker.max_depth(depth);
+        }
+        match self.jobs {
+            Some(1) => walker = walker.parallelism(jwalk::Parallelism::Serial),
+            Some(n) => walker = walker.parallelism(jwalk::Parallelism::RayonNewPool(n)),
+            None => {}
+        }
+        let respect_gitignore = self.respect_gitignore;
+        let skip_dirs = self.skip_dirs.clone();
+
         // Use jwalk for parallel directory walking
         // Skip hidden directories except .claude for performance
-        for entry in WalkDir::new(root)
-            .skip_hidden(false)
-            .process_read_dir(|_, _, _, children| {
+        for entry in walker
+            .process_read_dir(move |_, dir, gitignores, children| {
+                // Children inherit the matchers of their parents
+                if respect_gitignore {
+                    if let Some(gitignore) = load_gitignore(dir) {
+                        gitignores.push(Arc::new(gitignore));
+                    }
+                }
+
                 // Filter: keep .claude dirs, skip other hidden dirs
                 children.retain(|e| {
                     if let Ok(e) = e {
                         let name = e.file_name.to_string_lossy();
                         // Keep if it's .claude or not hidden
-                        name == ".claude" || !name.starts_with('.')
+                        if name == ".claude" {
+                            return true;
+                        }
+                        if name.starts_with('.') {
+                            return false;
+                        }
+                        if !e.file_type.is_dir() {
+                            return true;
+                        }
+                        // Prune skipped and gitignored directories
+                        let skipped = !skip_dirs.is_empty()
+                            && std::path::absolute(e.path()).is_ok_and(|p| skip_dirs.contains(&p));
+                        !skipped && !is_gitignored(gitignores, &e.path())
                     } else {
-                        false
+                        // Keep errors so they can be reported
+                        true
                     }
                 });
+
+                // Yield .claude before its siblings so it is sized while the
+                // rest of the project (often a large tree) is still walked
+                children.sort_by_key(|e| !matches!(e, Ok(e) if e.file_name == ".claude"));
             })
             .into_iter()
-            .flatten()
         {
+            if cancelled.load(Ordering::Relaxed) {
+                return dirs_walked;
+            }
+
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    let path = e.path().unwrap_or(root).to_path_buf();
+                    let _ = tx.send(ScanEvent::Error(path, e.to_string()));
+                    continue;
+                }
+            };
+            if entry.file_type().is_dir() {
+                dirs_walked += 1;
+            }
             let path = entry.path();
 
-            // Check if it's a .claude directory
-            if path.file_name().map(|n| n == ".claude").unwrap_or(false) && path.is_dir() {
+            // Check if it's a .claude directory (or file, when asked for)
+            let wanted = path.is_dir() || (self.include_files && path.is_file());
+            if path.file_name().map(|n| n == ".claude").unwrap_or(false) && wanted {
                 // Skip global ~/.claude unless include_global flag set
-                if !include_global && global_path.map(|g| path == g).unwrap_or(false) {
+                if !self.include_global && global_path.map(|g| path == g).unwrap_or(false) {
                     continue;
                 }
 
                 // Skip if matches exclusion pattern
-                if Self::should_exclude(&path, exclude_patterns) {
+                if self.should_exclude(&path) {
                     continue;
                 }
 
-                // Send progress update
+                // Skip folders already found under another root
+                if !seen.insert(path.clone()) {
+                    continue;
+                }
+
+                // Send progress update, then hand off to a sizing worker
                 let _ = tx.send(ScanEvent::Scanning(path.to_path_buf()));
+                let _ = work_tx.send((path, root.to_path_buf()));
+            }
+        }
+        dirs_walked
+    }
+
+    /// Size a found folder (or reuse its cached size) and report it
+    fn measure(
+        &self,
+        path: PathBuf,
+        root: PathBuf,
+        tx: &Sender<ScanEvent>,
+        cache: &Mutex<SizeCache>,
+    ) {
+        // Get modification time
+        let metadata = std::fs::metadata(&path).ok();
+        let modified_at = metadata.as_ref().and_then(|m| m.modified().ok());
+
+        // Stray `.claude` files (--include-files) are their own size
+        if let Some(m) = metadata.as_ref().filter(|m| m.is_file()) {
+            let size = if self.disk_usage {
+                disk_usage(m)
+            } else {
+                m.len()
+            };
+            if self.min_size.is_some_and(|min| size < min) {
+                return;
+            }
+            let folder = ClaudeFolder {
+                largest_file: Some((path.clone(), m.len())),
+                path,
+                size,
+                disk_size: disk_usage(m),
+                apparent_size: m.len(),
+                file_count: 1,
+                project_type: "File".to_string(),
+                selected: false,
+                modified_at,
+                root: Some(root),
+            };
+            let _ = tx.send(ScanEvent::Found(folder));
+            return;
+        }
 
-                // Calculate folder size
-                let size = calculate_dir_size(&path);
+        // A file can grow deep inside without touching the folder's own
+        // mtime, so the cache is keyed on the newest mtime anywhere in it
+        let newest = match (&self.size_cache, self.skip_sizes) {
+            (Some(_), false) => newest_mtime(&path, self.follow_symlinks),
+            _ => None,
+        };
+        let cached = newest.and_then(|m| cache.lock().unwrap().get(&path, m));
+        let (apparent, on_disk, files, largest_file) = match cached {
+            _ if self.skip_sizes => (0, 0, 0, None),
+            Some(cached) => (
+                cached.apparent,
+                cached.on_disk,
+                cached.files,
+                cached.largest,
+            ),
+            None => {
+                let size = calculate_dir_size(&path, self.follow_symlinks);
+                for (path, message) in &size.errors {
+                    let _ = tx.send(ScanEvent::Error(path.clone(), message.clone()));
+                }
+                // Under-counted sizes aren't worth remembering
+                if let (Some(modified_at), true) = (newest, size.errors.is_empty()) {
+                    cache.lock().unwrap().insert(
+                        path.clone(),
+                        CachedSize {
+                            modified_at,
+                            apparent: size.apparent,
+                            on_disk: size.on_disk,
+                            files: size.files,
+                            largest: size.largest.clone(),
+                        },
+                    );
+                }
+                (size.apparent, size.on_disk, size.files, size.largest)
+            }
+        };
 
-                // Detect project type from parent directory
-                let project_type = project::detect(&path);
+        let size = if self.disk_usage { on_disk } else { apparent };
+        if self.min_size.is_some_and(|min| size < min) {
+            return;
+        }
 
-                // Get modification time
-                let modified_at = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        // Detect project type from parent directory
+        let project_type =
+            project::detect(&path, &self.custom_markers, Some(&root), self.max_ancestors);
 
-                let folder = ClaudeFolder {
-                    path: path.to_path_buf(),
-                    size,
-                    project_type,
-                    selected: false,
-                    modified_at,
-                };
+        let folder = ClaudeFolder {
+            path,
+            size,
+            disk_size: on_disk,
+            apparent_size: apparent,
+            file_count: files,
+            largest_file,
+            project_type,
+            selected: false,
+            modified_at,
+            root: Some(root),
+        };
 
-                let _ = tx.send(ScanEvent::Found(folder));
-            }
+        let _ = tx.send(ScanEvent::Found(folder));
+    }
+}
+
+/// Parse `dir/.gitignore`, if there is one
+fn load_gitignore(dir: &Path) -> Option<Gitignore> {
+    let file = dir.join(".gitignore");
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    builder.add(file);
+    builder.build().ok()
+}
+
+/// Whether the nearest `.gitignore` rule matching `dir` ignores it
+fn is_gitignored(gitignores: &[Arc<Gitignore>], dir: &Path) -> bool {
+    for gitignore in gitignores.iter().rev() {
+        let matched = gitignore.matched(dir, true);
+        if matched.is_ignore() {
+            return true;
+        }
+        if matched.is_whitelist() {
+            return false;
         }
     }
+    false
+}
+
+/// Apparent (logical) and on-disk sizes of a directory
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DirSize {
+    pub apparent: u64,
+    pub on_disk: u64,
+    /// Files counted (directories excluded)
+    pub files: u64,
+    /// Biggest file by apparent size
+    pub largest: Option<(PathBuf, u64)>,
+    /// Unreadable entries, which make the totals an under-count
+    pub errors: Vec<(PathBuf, String)>,
 }
 
 /// Calculate total size of a directory recursively
-fn calculate_dir_size(path: &Path) -> u64 {
+///
+/// Symlinks are counted as links, not by their targets, unless
+/// `follow_symlinks` is set.
+pub fn calculate_dir_size(path: &Path, follow_symlinks: bool) -> DirSize {
+    let mut size = DirSize::default();
+
+    // Serial walk: folders are already sized in parallel by the scan workers
+    for entry in WalkDir::new(path)
+        .skip_hidden(false)
+        .follow_links(follow_symlinks)
+        .parallelism(jwalk::Parallelism::Serial)
+    {
+        let metadata = match entry {
+            Ok(entry) => entry
+                .metadata()
+                .map(|m| (entry.path(), m))
+                .map_err(|e| (entry.path(), e.to_string())),
+            Err(e) => Err((e.path().unwrap_or(path).to_path_buf(), e.to_string())),
+        };
+        match metadata {
+            Ok((file, m)) if m.is_file() => {
+                size.apparent += m.len();
+                size.on_disk += disk_usage(&m);
+                size.files += 1;
+                if size.largest.as_ref().is_none_or(|(_, len)| m.len() > *len) {
+                    size.largest = Some((file, m.len()));
+                }
+            }
+            Ok(_) => {}
+            Err(error) => size.errors.push(error),
+        }
+    }
+
+    size
+}
+
+/// Latest modification time of `path` and everything under it, or None if
+/// any entry can't be read
+fn newest_mtime(path: &Path, follow_symlinks: bool) -> Option<SystemTime> {
     WalkDir::new(path)
         .skip_hidden(false)
+        .follow_links(follow_symlinks)
+        .parallelism(jwalk::Parallelism::Serial)
         .into_iter()
-        .filter_map(|e| e.ok())
-        .filter_map(|e| e.metadata().ok())
-        .filter(|m| m.is_file())
-        .map(|m| m.len())
-        .sum()
+        .map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+        .try_fold(SystemTime::UNIX_EPOCH, |newest, m| Some(newest.max(m?)))
+}
+
+/// Current apparent size of a found folder (or stray `.claude` file)
+pub fn apparent_size(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(m) if m.is_file() => m.len(),
+        Ok(_) => calculate_dir_size(path, false).apparent,
+        Err(_) => 0,
+    }
+}
+
+/// Re-measure folders (path, apparent size when scanned) and return those
+/// that grew by more than 20% since, e.g. because a session is writing to
+/// them. Walks every folder, so keep it off the UI thread
+pub fn grown_since_scan(folders: &[(PathBuf, u64)]) -> Vec<PathBuf> {
+    folders
+        .iter()
+        .filter(|(path, scanned)| apparent_size(path).saturating_mul(5) > scanned.saturating_mul(6))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+/// One immediate child of a folder, shown in the delete preview
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChildEntry {
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+/// List the immediate children of `path` with their sizes, largest first
+///
+/// Subdirectories are sized recursively, so this walks the whole folder.
+pub fn list_children(path: &Path) -> Vec<ChildEntry> {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    let mut children: Vec<ChildEntry> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+            let is_dir = metadata.is_dir();
+            let size = if is_dir {
+                calculate_dir_size(&entry.path(), false).apparent
+            } else {
+                metadata.len()
+            };
+            Some(ChildEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                size,
+                is_dir,
+            })
+        })
+        .collect();
+
+    children.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    children
+}
+
+/// Bytes allocated on disk for a file
+#[cfg(unix)]
+fn disk_usage(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+/// Bytes allocated on disk for a file (block counts unavailable here)
+#[cfg(not(unix))]
+fn disk_usage(metadata: &std::fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Scan roots must not be hidden, or the hidden-dir filter prunes them
+    fn scan_root() -> tempfile::TempDir {
+        tempfile::Builder::new()
+            .prefix("claudekill-test")
+            .tempdir()
+            .unwrap()
+    }
+
+    fn collect_found(scanner: &Scanner) -> Vec<PathBuf> {
+        let mut found: Vec<PathBuf> = scanner
+            .scan()
+            .rx
+            .into_iter()
+            .filter_map(|e| match e {
+                ScanEvent::Found(f) => Some(f.path),
+                _ => None,
+            })
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_scan_respects_max_depth() {
+        let temp = scan_root();
+        let shallow = temp.path().join("a/.claude");
+        let deep = temp.path().join("a/b/c/.claude");
+        fs::create_dir_all(&shallow).unwrap();
+        fs::create_dir_all(&deep).unwrap();
+
+        let scanner = Scanner::new(vec![temp.path().to_path_buf()], false, vec![]);
+        assert_eq!(collect_found(&scanner), vec![shallow.clone(), deep]);
+
+        let scanner = scanner.with_max_depth(Some(2));
+        assert_eq!(collect_found(&scanner), vec![shallow]);
+
+        let scanner =
+            Scanner::new(vec![temp.path().to_path_buf()], false, vec![]).with_max_depth(Some(0));
+        assert!(collect_found(&scanner).is_empty());
+    }
+
+    #[test]
+    fn test_scan_with_jobs() {
+        let temp = scan_root();
+        let mut expected: Vec<PathBuf> = ["a", "b/c", "d/e/f"]
+            .iter()
+            .map(|dir| temp.path().join(dir).join(".claude"))
+            .collect();
+        for path in &expected {
+            fs::create_dir_all(path).unwrap();
+        }
+        expected.sort();
+
+        // Sequential and bounded-parallel walks find the same folders
+        for jobs in [1, 2] {
+            let scanner =
+                Scanner::new(vec![temp.path().to_path_buf()], false, vec![]).with_jobs(Some(jobs));
+            assert_eq!(collect_found(&scanner), expected);
+        }
+    }
+
+    #[test]
+    fn test_grown_since_scan() {
+        let temp = tempdir().unwrap();
+        let mut scanned = Vec::new();
+        for (name, now) in [("steady", 110), ("growing", 200)] {
+            let path = temp.path().join(name).join(".claude");
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join("log"), vec![b'x'; now]).unwrap();
+            scanned.push((path, 100));
+        }
+
+        // 10% growth is noise; doubling isn't
+        assert_eq!(
+            grown_since_scan(&scanned),
+            vec![temp.path().join("growing/.claude")]
+        );
+    }
+
+    #[test]
+    fn test_scan_skips_quarantine_dir() {
+        let temp = scan_root();
+        let project = temp.path().join("app/.claude");
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(temp.path().join("quarantine/old/app/.claude")).unwrap();
+
+        let scanner = Scanner::new(vec![temp.path().to_path_buf()], false, vec![])
+            .with_skipped_dirs(vec![temp.path().join("quarantine")]);
+        assert_eq!(collect_found(&scanner), vec![project]);
+    }
+
+    #[test]
+    fn test_scan_multiple_roots_deduplicates() {
+        let temp = scan_root();
+        let work = temp.path().join("work");
+        let personal = temp.path().join("personal");
+        let a = work.join("a/.claude");
+        let b = personal.join("b/.claude");
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+
+        // `work/a` overlaps with `work`
+        let scanner = Scanner::new(vec![work.clone(), personal, work.join("a")], false, vec![]);
+        let mut expected = vec![a.clone(), b];
+        expected.sort();
+        assert_eq!(collect_found(&scanner), expected);
+
+        // Each folder is tagged with the first root that reached it
+        let root_of_a = scanner.scan().rx.into_iter().find_map(|e| match e {
+            ScanEvent::Found(f) if f.path == a => f.root,
+            _ => None,
+        });
+        assert_eq!(root_of_a, Some(work));
+    }
+
+    #[test]
+    fn test_scan_respects_gitignore() {
+        let root = scan_root();
+        fs::write(root.path().join(".gitignore"), "build/\n.claude/\n").unwrap();
+        fs::create_dir_all(root.path().join("build/out/.claude")).unwrap();
+        fs::create_dir_all(root.path().join("app/.claude")).unwrap();
+        fs::write(root.path().join("app/.gitignore"), "vendor\n").unwrap();
+        fs::create_dir_all(root.path().join("app/vendor/lib/.claude")).unwrap();
+
+        let scanner = Scanner::new(vec![root.path().to_path_buf()], false, vec![]);
+        assert_eq!(collect_found(&scanner).len(), 3);
+
+        let found = collect_found(&scanner.with_respect_gitignore(true));
+        assert_eq!(found, vec![root.path().join("app/.claude")]);
+    }
+
+    #[test]
+    fn test_scan_counts_dirs_walked() {
+        let root = scan_root();
+        fs::create_dir_all(root.path().join("a/b/.claude")).unwrap();
+        fs::create_dir_all(root.path().join("c")).unwrap();
+
+        let handle = Scanner::new(vec![root.path().to_path_buf()], false, vec![]).scan();
+        handle
+            .rx
+            .iter()
+            .find(|e| matches!(e, ScanEvent::Complete))
+            .unwrap();
+        // root, a, a/b, a/b/.claude, c
+        assert_eq!(handle.dirs_walked(), 5);
+    }
+
+    #[test]
+    fn test_scan_reuses_cached_sizes() {
+        let root = scan_root();
+        let claude = root.path().join("app/.claude");
+        fs::create_dir_all(claude.join("logs")).unwrap();
+        fs::write(claude.join("logs/run.log"), vec![b'x'; 100]).unwrap();
+
+        let cache_dir = tempdir().unwrap();
+        let scanner = Scanner::new(vec![root.path().to_path_buf()], false, vec![])
+            .with_size_cache(Some(cache_dir.path().join("sizes.json")));
+        let sizes = |scanner: &Scanner| -> Vec<u64> {
+            scanner
+                .scan()
+                .rx
+                .into_iter()
+                .filter_map(|e| match e {
+                    ScanEvent::Found(f) => Some(f.size),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(sizes(&scanner), vec![100]);
+
+        // Unchanged folders come from the cache
+        let cache = SizeCache::load_from(&cache_dir.path().join("sizes.json"));
+        assert_eq!(cache.entries[&claude].apparent, 100);
+        assert_eq!(sizes(&scanner), vec![100]);
+
+        // Growing a nested file leaves the folder's mtime alone but still
+        // invalidates it
+        fs::write(claude.join("logs/run.log"), vec![b'x'; 300]).unwrap();
+        assert_eq!(sizes(&scanner), vec![300]);
+
+        // So does adding an entry to the folder itself
+        fs::write(claude.join("new.txt"), vec![b'x'; 5]).unwrap();
+        assert_eq!(sizes(&scanner), vec![305]);
+    }
+
+    #[test]
+    fn test_scan_include_files() {
+        let root = scan_root();
+        fs::create_dir_all(root.path().join("app/.claude")).unwrap();
+        fs::create_dir_all(root.path().join("tool")).unwrap();
+        fs::write(root.path().join("tool/.claude"), vec![b'x'; 42]).unwrap();
+
+        let scanner = Scanner::new(vec![root.path().to_path_buf()], false, vec![]);
+        assert_eq!(collect_found(&scanner).len(), 1);
+
+        let files: Vec<ClaudeFolder> = scanner
+            .with_include_files(true)
+            .scan()
+            .rx
+            .into_iter()
+            .filter_map(|e| match e {
+                ScanEvent::Found(f) if f.project_type == "File" => Some(f),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, root.path().join("tool/.claude"));
+        assert_eq!((files[0].size, files[0].file_count), (42, 1));
+    }
+
+    #[test]
+    fn test_scan_skip_sizes() {
+        let root = scan_root();
+        fs::create_dir_all(root.path().join("app/.claude")).unwrap();
+        fs::write(root.path().join("app/.claude/run.log"), vec![b'x'; 100]).unwrap();
+
+        let scanner =
+            Scanner::new(vec![root.path().to_path_buf()], false, vec![]).with_skip_sizes(true);
+        let sizes: Vec<u64> = scanner
+            .scan()
+            .rx
+            .into_iter()
+            .filter_map(|e| match e {
+                ScanEvent::Found(f) => Some(f.size),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sizes, vec![0]);
+    }
+
+    #[test]
+    fn test_exclude_patterns() {
+        let exclude = ExcludeMatcher::new(&[
+            "node_modules/**".to_string(),
+            "/srv/**/cache/*".to_string(),
+            "archive".to_string(),
+        ]);
+
+        assert!(exclude.is_match(Path::new("/home/u/app/node_modules/pkg/.claude")));
+        assert!(!exclude.is_match(Path::new("/home/u/node_modules_backup/.claude")));
+        assert!(exclude.is_match(Path::new("/srv/a/b/cache/.claude")));
+        assert!(!exclude.is_match(Path::new("/srv/a/cache/x/.claude")));
+
+        // Plain patterns still match as substrings
+        assert!(exclude.is_match(Path::new("/home/u/old-archive-2020/.claude")));
+        assert!(!exclude.is_match(Path::new("/home/u/app/.claude")));
+    }
+
+    #[test]
+    fn test_cancelled_scan_stops_early() {
+        let temp = scan_root();
+        fs::create_dir_all(temp.path().join("a/.claude")).unwrap();
+
+        let scanner = Scanner::new(vec![temp.path().to_path_buf()], false, vec![]);
+        let handle = scanner.scan_with_flag(Arc::new(AtomicBool::new(true)));
+        assert!(handle.is_cancelled());
+
+        let events: Vec<ScanEvent> = handle.rx.into_iter().collect();
+        assert!(matches!(events.as_slice(), [ScanEvent::Complete]));
+    }
+
+    #[test]
+    fn test_scan_respects_min_size() {
+        let temp = scan_root();
+        let small = temp.path().join("small/.claude");
+        let large = temp.path().join("large/.claude");
+        fs::create_dir_all(&small).unwrap();
+        fs::create_dir_all(&large).unwrap();
+        fs::write(small.join("a.txt"), vec![b'x'; 100]).unwrap();
+        fs::write(large.join("a.txt"), vec![b'x'; 10_000]).unwrap();
+
+        let scanner =
+            Scanner::new(vec![temp.path().to_path_buf()], false, vec![]).with_min_size(Some(1024));
+        assert_eq!(collect_found(&scanner), vec![large]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_scan_disk_usage_counts_blocks() {
+        let temp = scan_root();
+        let claude = temp.path().join("p/.claude");
+        fs::create_dir_all(&claude).unwrap();
+        // Sparse file: long, but with (almost) nothing allocated
+        fs::File::create(claude.join("sparse"))
+            .unwrap()
+            .set_len(10_000_000)
+            .unwrap();
+
+        let found = |disk_usage: bool| {
+            let scanner = Scanner::new(vec![temp.path().to_path_buf()], false, vec![])
+                .with_disk_usage(disk_usage);
+            scanner
+                .scan()
+                .rx
+                .into_iter()
+                .find_map(|e| match e {
+                    ScanEvent::Found(f) => Some(f),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        let apparent = found(false);
+        assert_eq!(apparent.size, 10_000_000);
+        assert_eq!(apparent.apparent_size, 10_000_000);
+
+        let blocks = found(true);
+        assert_eq!(blocks.size, blocks.disk_size);
+        assert_eq!(blocks.apparent_size, 10_000_000);
+        assert!(blocks.size < blocks.apparent_size);
+        assert_eq!(blocks.other_size(), Some(10_000_000));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_calculate_dir_size_reports_unreadable_dirs() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempdir().unwrap();
+        let locked = temp.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::write(locked.join("secret.txt"), "x").unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+        // Root ignores permissions, so there's nothing to test there
+        let readable = fs::read_dir(&locked).is_ok();
+        let size = calculate_dir_size(temp.path(), false);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        if readable {
+            return;
+        }
+        assert_eq!(size.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_calculate_dir_size_regular_file() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("log.txt"), vec![b'x'; 10_000]).unwrap();
+        fs::create_dir(temp.path().join("sub")).unwrap();
+        fs::write(temp.path().join("sub/a.json"), "{}").unwrap();
+
+        let size = calculate_dir_size(temp.path(), false);
+        assert_eq!(size.apparent, 10_002);
+        // Directories aren't files
+        assert_eq!(size.files, 2);
+        assert_eq!(size.largest, Some((temp.path().join("log.txt"), 10_000)));
+        #[cfg(unix)]
+        assert!(size.on_disk >= 10_000);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_calculate_dir_size_sparse_file() {
+        let temp = tempdir().unwrap();
+        let file = fs::File::create(temp.path().join("sparse.bin")).unwrap();
+        file.set_len(64 * 1024 * 1024).unwrap();
+
+        let size = calculate_dir_size(temp.path(), false);
+        assert_eq!(size.apparent, 64 * 1024 * 1024);
+        assert!(size.on_disk < size.apparent);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_calculate_dir_size_symlinked_dir() {
+        let temp = tempdir().unwrap();
+        let shared = temp.path().join("shared");
+        fs::create_dir(&shared).unwrap();
+        fs::write(shared.join("big.bin"), vec![b'x'; 50_000]).unwrap();
+
+        let claude = temp.path().join(".claude");
+        fs::create_dir(&claude).unwrap();
+        fs::write(claude.join("own.txt"), vec![b'x'; 100]).unwrap();
+        std::os::unix::fs::symlink(&shared, claude.join("link")).unwrap();
+
+        assert_eq!(calculate_dir_size(&claude, false).apparent, 100);
+        assert_eq!(calculate_dir_size(&claude, true).apparent, 50_100);
+    }
+
+    #[test]
+    fn test_list_children_sorted_by_size() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("small.txt"), vec![b'x'; 10]).unwrap();
+        fs::create_dir(temp.path().join("logs")).unwrap();
+        fs::write(temp.path().join("logs/a.log"), vec![b'x'; 500]).unwrap();
+        fs::write(temp.path().join("logs/b.log"), vec![b'x'; 500]).unwrap();
+        fs::write(temp.path().join("cache.bin"), vec![b'x'; 300]).unwrap();
+
+        let children = list_children(temp.path());
+        let summary: Vec<_> = children
+            .iter()
+            .map(|c| (c.name.as_str(), c.size, c.is_dir))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("logs", 1000, true),
+                ("cache.bin", 300, false),
+                ("small.txt", 10, false)
+            ]
+        );
+        assert!(list_children(&temp.path().join("missing")).is_empty());
+    }
 }
@@ -0,0 +1,301 @@
+//! Persistent runtime state carried between runs
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// State remembered across runs (stored in the cache dir, not user config)
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    /// Duration of the last completed scan, keyed by scan root
+    pub scan_durations: HashMap<PathBuf, Duration>,
+    /// TUI view settings at the last quit (see `remember_state`)
+    pub ui: Option<UiState>,
+    /// The user agreed to scan their whole home directory (see `confirm_home_scan`)
+    pub home_scan_confirmed: bool,
+}
+
+/// TUI view settings restored on the next launch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiState {
+    /// `SortOrder::name` of the last sort order
+    pub sort_order: String,
+    pub show_filter_bar: bool,
+}
+
+impl State {
+    /// Load state from disk
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::state_path())
+    }
+
+    /// Load state from a specific file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state: {}", path.display()))?;
+
+        serde_json::from_str(&content).with_context(|| "Failed to parse state")
+    }
+
+    /// Save state to disk
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::state_path())
+    }
+
+    /// Save state to a specific file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Duration of the last completed scan of `root`
+    pub fn last_scan_duration(&self, root: &Path) -> Option<Duration> {
+        self.scan_durations.get(root).copied()
+    }
+
+    /// Remember how long a scan of `root` took
+    pub fn set_scan_duration(&mut self, root: &Path, duration: Duration) {
+        self.scan_durations.insert(root.to_path_buf(), duration);
+    }
+
+    /// Get state file path
+    pub fn state_path() -> PathBuf {
+        cache_file("state.json")
+    }
+}
+
+/// Sizes computed on a previous run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedSize {
+    /// Newest modification time anywhere in the folder when it was sized
+    pub modified_at: SystemTime,
+    pub apparent: u64,
+    pub on_disk: u64,
+    /// Required, so caches from before file counts fail to load and are rebuilt
+    pub files: u64,
+    /// Biggest file inside and its size
+    pub largest: Option<(PathBuf, u64)>,
+}
+
+/// Folder sizes memoized by path and modification time
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SizeCache {
+    pub entries: HashMap<PathBuf, CachedSize>,
+}
+
+impl SizeCache {
+    /// Load the cache from a file (missing or unreadable = empty)
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the cache to a file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::utils::write_atomic(path, &serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Cached sizes for `path`, if nothing in it has been modified since
+    pub fn get(&self, path: &Path, modified_at: SystemTime) -> Option<CachedSize> {
+        self.entries
+            .get(path)
+            .cloned()
+            .filter(|cached| cached.modified_at == modified_at)
+    }
+
+    pub fn insert(&mut self, path: PathBuf, size: CachedSize) {
+        self.entries.insert(path, size);
+    }
+
+    /// Get size cache file path
+    pub fn cache_path() -> PathBuf {
+        cache_file("sizes.json")
+    }
+}
+
+/// Totals of one `--report` run, appended to the trend log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendSnapshot {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Roots that were scanned, as shown by `Scanner::roots_display`
+    pub roots: String,
+    pub total_folders: usize,
+    pub total_size: u64,
+}
+
+impl TrendSnapshot {
+    /// Append this snapshot as one JSON line
+    pub fn append_to(&self, path: &Path) -> Result<()> {
+        use std::io::Write;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open trend log: {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The last `limit` snapshots of scans over `roots`, oldest first
+    /// (unreadable lines are skipped)
+    pub fn load_recent_from(path: &Path, roots: &str, limit: usize) -> Vec<Self> {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let matching: Vec<Self> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Self>(line).ok())
+            .filter(|s| s.roots == roots)
+            .collect();
+        let skip = matching.len().saturating_sub(limit);
+        matching.into_iter().skip(skip).collect()
+    }
+
+    /// Get trend log path
+    pub fn trend_path() -> PathBuf {
+        cache_file("trend.jsonl")
+    }
+}
+
+/// Path of a file in the claudekill cache dir
+fn cache_file(name: &str) -> PathBuf {
+    ProjectDirs::from("", "", "claudekill")
+        .map(|dirs| dirs.cache_dir().join(name))
+        .unwrap_or_else(|| {
+            dirs::cache_dir()
+                .unwrap_or_default()
+                .join("claudekill")
+                .join(name)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_scan_duration_roundtrip() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("state.json");
+        let root = PathBuf::from("/home/user/Projects");
+
+        let mut state = State::default();
+        state.set_scan_duration(&root, Duration::from_secs(12));
+        state.save_to(&path).unwrap();
+
+        let loaded = State::load_from(&path).unwrap();
+        assert_eq!(
+            loaded.last_scan_duration(&root),
+            Some(Duration::from_secs(12))
+        );
+        assert_eq!(loaded.last_scan_duration(Path::new("/other")), None);
+    }
+
+    #[test]
+    fn test_ui_state_roundtrip() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("state.json");
+
+        // State files from before `ui` existed still load
+        fs::write(&path, r#"{"scan_durations":{}}"#).unwrap();
+        let mut state = State::load_from(&path).unwrap();
+        assert_eq!(state.ui, None);
+
+        state.ui = Some(UiState {
+            sort_order: "name_asc".to_string(),
+            show_filter_bar: true,
+        });
+        state.save_to(&path).unwrap();
+        assert_eq!(State::load_from(&path).unwrap().ui, state.ui);
+    }
+
+    #[test]
+    fn test_load_missing_state_is_default() {
+        let temp = tempdir().unwrap();
+        let state = State::load_from(&temp.path().join("missing.json")).unwrap();
+        assert!(state.scan_durations.is_empty());
+    }
+
+    #[test]
+    fn test_size_cache_invalidated_by_mtime() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("sizes.json");
+        let folder = PathBuf::from("/p/.claude");
+        let modified_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut cache = SizeCache::default();
+        cache.insert(
+            folder.clone(),
+            CachedSize {
+                modified_at,
+                apparent: 10,
+                on_disk: 4096,
+                files: 1,
+                largest: None,
+            },
+        );
+        cache.save_to(&path).unwrap();
+
+        let loaded = SizeCache::load_from(&path);
+        assert_eq!(
+            loaded.get(&folder, modified_at).map(|c| c.apparent),
+            Some(10)
+        );
+        assert!(loaded
+            .get(&folder, modified_at + Duration::from_secs(1))
+            .is_none());
+    }
+
+    #[test]
+    fn test_trend_snapshots_append_and_filter() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("trend.jsonl");
+        let snapshot = |roots: &str, total_size| TrendSnapshot {
+            timestamp: chrono::Utc::now(),
+            roots: roots.to_string(),
+            total_folders: 1,
+            total_size,
+        };
+
+        for size in [10, 20, 30] {
+            snapshot("/home", size).append_to(&path).unwrap();
+        }
+        snapshot("/srv", 99).append_to(&path).unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "not json\n").unwrap();
+
+        let recent = TrendSnapshot::load_recent_from(&path, "/home", 2);
+        let sizes: Vec<u64> = recent.iter().map(|s| s.total_size).collect();
+        assert_eq!(sizes, vec![20, 30]);
+        assert!(TrendSnapshot::load_recent_from(&temp.path().join("none"), "/home", 5).is_empty());
+    }
+
+    #[test]
+    fn test_state_path_not_empty() {
+        let path = State::state_path();
+        assert!(!path.as_os_str().is_empty());
+    }
+}
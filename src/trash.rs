@@ -1,30 +1,444 @@
 // Trash module - handles moving folders to Trash or permanent deletion
 
 use anyhow::{Context, Result};
-use std::ffi::OsStr;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::{OsStr, OsString};
 use std::fs;
-use std::path::PathBuf;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Identifies the exact Trash entry a folder was moved to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashItemInfo {
+    /// Platform trash id (info file on Linux, parsing name on Windows)
+    pub id: OsString,
+    pub original_path: PathBuf,
+    pub time_deleted: i64,
+}
+
+/// Running totals reported while folders are being deleted
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeleteProgress {
+    /// Folders fully handled
+    pub folders: usize,
+    /// Files removed so far (permanent deletion only)
+    pub files: u64,
+    /// Bytes removed so far (permanent deletion only)
+    pub bytes: u64,
+}
 
 /// Move folders to system Trash/Recycle Bin
-pub fn move_to_trash(paths: &[PathBuf]) -> Result<()> {
+///
+/// Returns the Trash entries created, where the platform lets us list them
+/// (Linux and Windows); elsewhere the list is empty.
+pub fn move_to_trash(
+    paths: &[PathBuf],
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(DeleteProgress),
+) -> Result<Vec<TrashItemInfo>> {
+    let mut done = DeleteProgress::default();
     for path in paths {
+        check_cancelled(cancel)?;
         trash::delete(path)
             .with_context(|| format!("Failed to move to trash: {}", path.display()))?;
+        done.folders += 1;
+        progress(done);
     }
-    Ok(())
+    Ok(find_trash_items(paths))
+}
+
+/// Look up the newest Trash entry for each deleted path
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn find_trash_items(paths: &[PathBuf]) -> Vec<TrashItemInfo> {
+    let items = trash::os_limited::list().unwrap_or_default();
+    paths
+        .iter()
+        .filter_map(|path| {
+            items
+                .iter()
+                .filter(|item| item.original_path() == *path)
+                .max_by_key(|item| item.time_deleted)
+                .map(|item| TrashItemInfo {
+                    id: item.id.clone(),
+                    original_path: path.clone(),
+                    time_deleted: item.time_deleted,
+                })
+        })
+        .collect()
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+fn find_trash_items(_paths: &[PathBuf]) -> Vec<TrashItemInfo> {
+    Vec::new()
 }
 
-/// Permanently delete folders (bypass Trash)
-pub fn permanent_delete(paths: &[PathBuf]) -> Result<()> {
+/// Restore the exact Trash entry recorded at deletion time
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn restore_trash_item(info: &TrashItemInfo) -> Result<()> {
+    let item = trash::os_limited::list()
+        .context("Failed to list Trash")?
+        .into_iter()
+        .find(|item| item.id == info.id)
+        .with_context(|| format!("No longer in Trash: {}", info.original_path.display()))?;
+
+    trash::os_limited::restore_all([item])
+        .with_context(|| format!("Failed to restore: {}", info.original_path.display()))
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn restore_trash_item(info: &TrashItemInfo) -> Result<()> {
+    anyhow::bail!(
+        "Trash entries can't be restored on this platform: {}",
+        info.original_path.display()
+    )
+}
+
+/// Permanently delete folders (bypass Trash), reporting progress after
+/// each top-level entry of a folder
+pub fn permanent_delete(
+    paths: &[PathBuf],
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(DeleteProgress),
+) -> Result<()> {
+    let mut done = DeleteProgress::default();
     for path in paths {
-        fs::remove_dir_all(path)
+        check_cancelled(cancel)?;
+        remove_counting(path, &mut done, cancel, progress)
             .with_context(|| format!("Failed to delete: {}", path.display()))?;
+        done.folders += 1;
+        progress(done);
+    }
+    Ok(())
+}
+
+/// Write a `.tar.gz` of each folder into `backup_dir` before deleting it
+///
+/// Archives are named `<path hash>-<timestamp>.tar.gz` and hold the folder
+/// under its own name (`.claude/...`). Returns the archive of each path.
+pub fn backup_folders(paths: &[PathBuf], backup_dir: &Path) -> Result<Vec<PathBuf>> {
+    // Recorded in history, so it must not depend on the working directory
+    let backup_dir = &std::path::absolute(backup_dir)?;
+    fs::create_dir_all(backup_dir)
+        .with_context(|| format!("Failed to create: {}", backup_dir.display()))?;
+
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut archives = Vec::with_capacity(paths.len());
+    for path in paths {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        let base = format!("{:016x}-{}", hasher.finish(), stamp);
+
+        let mut archive = backup_dir.join(format!("{}.tar.gz", base));
+        let mut n = 1;
+        while archive.exists() {
+            archive = backup_dir.join(format!("{}-{}.tar.gz", base, n));
+            n += 1;
+        }
+
+        if let Err(e) = write_archive(path, &archive) {
+            let _ = fs::remove_file(&archive);
+            return Err(e).with_context(|| format!("Failed to back up: {}", path.display()));
+        }
+        archives.push(archive);
+    }
+    Ok(archives)
+}
+
+fn write_archive(path: &Path, archive: &Path) -> std::io::Result<()> {
+    let file = fs::File::create(archive)?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    // Symlinks are archived as links, like permanent_delete removes them
+    builder.follow_symlinks(false);
+    let name = path.file_name().unwrap_or(OsStr::new(".claude"));
+    if path.is_file() {
+        builder.append_path_with_name(path, name)?;
+    } else {
+        builder.append_dir_all(name, path)?;
+    }
+    builder.into_inner()?.finish()?.sync_all()
+}
+
+/// Stop a deletion between entries once `cancel` is set
+fn check_cancelled(cancel: &AtomicBool) -> std::io::Result<()> {
+    if cancel.load(Ordering::Relaxed) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Interrupted,
+            "Deletion cancelled",
+        ));
+    }
+    Ok(())
+}
+
+/// Remove a `.claude` folder, or a stray `.claude` file (`--include-files`)
+fn remove_counting(
+    path: &Path,
+    done: &mut DeleteProgress,
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(DeleteProgress),
+) -> std::io::Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.is_dir() {
+        return remove_dir_counting(path, done, cancel, progress);
+    }
+    fs::remove_file(path)?;
+    done.files += 1;
+    done.bytes += metadata.len();
+    progress(*done);
+    Ok(())
+}
+
+/// Remove a folder one top-level entry at a time, each subdirectory with
+/// `fs::remove_dir_all`, reporting progress and checking `cancel` in between
+fn remove_dir_counting(
+    path: &Path,
+    done: &mut DeleteProgress,
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(DeleteProgress),
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        check_cancelled(cancel)?;
+        let entry = entry?;
+        let entry_path = entry.path();
+        // Not followed: symlinks are removed, never their targets
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            // Measured first, as remove_dir_all can't report what it removed
+            let size = crate::scanner::calculate_dir_size(&entry_path, false);
+            fs::remove_dir_all(&entry_path)?;
+            done.files += size.files;
+            done.bytes += size.apparent;
+        } else {
+            let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            fs::remove_file(&entry_path).or_else(|e| {
+                // Directory symlinks on Windows
+                if file_type.is_symlink() {
+                    fs::remove_dir(&entry_path)
+                } else {
+                    Err(e)
+                }
+            })?;
+            done.files += 1;
+            done.bytes += len;
+        }
+        progress(*done);
+    }
+    fs::remove_dir(path)
+}
+
+/// Move folders into a quarantine directory instead of the system Trash
+///
+/// Each folder keeps its original path structure under `quarantine_dir`
+/// (e.g. `/home/me/app/.claude` -> `<dir>/home/me/app/.claude`) so folders
+/// from different projects never clash. The destination of each moved path
+/// is pushed to `destinations`, so it holds the folders already moved when a
+/// later one fails.
+pub fn move_to_quarantine(
+    paths: &[PathBuf],
+    quarantine_dir: &Path,
+    destinations: &mut Vec<PathBuf>,
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(DeleteProgress),
+) -> Result<()> {
+    let mut done = DeleteProgress::default();
+    for path in paths {
+        check_cancelled(cancel)?;
+        let dest = quarantine_destination(quarantine_dir, path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create: {}", parent.display()))?;
+        }
+        move_dir(path, &dest)
+            .with_context(|| format!("Failed to quarantine: {}", path.display()))?;
+        destinations.push(dest);
+        done.folders += 1;
+        progress(done);
     }
     Ok(())
 }
 
+/// Move a quarantined folder back to its original location
+pub fn restore_from_quarantine(quarantined: &Path, original: &Path) -> Result<()> {
+    if original.exists() {
+        anyhow::bail!("Original location already exists: {}", original.display());
+    }
+    if !quarantined.exists() {
+        anyhow::bail!("Quarantined folder is missing: {}", quarantined.display());
+    }
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    move_dir(quarantined, original)
+        .with_context(|| format!("Failed to restore: {}", original.display()))
+}
+
+/// Extract a `backup_folders` archive back to where the folder was
+pub fn restore_from_backup(archive: &Path, original: &Path) -> Result<()> {
+    if original.exists() {
+        anyhow::bail!("Original location already exists: {}", original.display());
+    }
+    if !archive.is_file() {
+        anyhow::bail!("Backup archive is missing: {}", archive.display());
+    }
+    let parent = original.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+
+    // The archive holds the folder under its own name, so unpack beside it
+    let file = fs::File::open(archive)?;
+    let mut unpacker = tar::Archive::new(GzDecoder::new(file));
+    unpacker.set_preserve_mtime(true);
+    if let Err(e) = unpacker.unpack(parent) {
+        let _ = fs::remove_dir_all(original);
+        return Err(e).with_context(|| format!("Failed to restore: {}", original.display()));
+    }
+    if !original.exists() {
+        anyhow::bail!(
+            "Backup {} does not contain {}",
+            archive.display(),
+            original.display()
+        );
+    }
+    Ok(())
+}
+
+/// Pick a free destination for `path` inside the quarantine directory
+fn quarantine_destination(quarantine_dir: &Path, path: &Path) -> PathBuf {
+    let relative: PathBuf = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part),
+            Component::Prefix(prefix) => Some(prefix.as_os_str()),
+            _ => None,
+        })
+        .map(|part| part.to_string_lossy().replace(':', ""))
+        .collect();
+    let base = quarantine_dir.join(relative);
+
+    // Same folder quarantined again (e.g. recreated after an earlier run)
+    let mut dest = base.clone();
+    let mut n = 1;
+    while dest.exists() {
+        let mut name = base.file_name().map(OsString::from).unwrap_or_default();
+        name.push(format!("-{}", n));
+        dest = base.with_file_name(name);
+        n += 1;
+    }
+    dest
+}
+
+/// Move a directory, falling back to copy + delete across filesystems
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {}
+        Err(e) => return Err(e.into()),
+    }
+    let file_type = fs::symlink_metadata(from)?.file_type();
+    if file_type.is_dir() {
+        copy_dir_all(from, to)?;
+        fs::remove_dir_all(from)?;
+    } else {
+        copy_entry(from, to, file_type)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+/// Copy a directory tree, recreating symlinks rather than following them
+fn copy_dir_all(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else {
+            copy_entry(&entry.path(), &target, file_type)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy a file, or a symlink as a link to the same target
+fn copy_entry(from: &Path, to: &Path, file_type: fs::FileType) -> std::io::Result<()> {
+    if !file_type.is_symlink() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    let target = fs::read_link(from)?;
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(target, to)
+    }
+    #[cfg(windows)]
+    {
+        if from.is_dir() {
+            std::os::windows::fs::symlink_dir(target, to)
+        } else {
+            std::os::windows::fs::symlink_file(target, to)
+        }
+    }
+}
+
+/// Whether `path` is at or under one of the `protected` paths
+pub fn is_protected(path: &Path, protected: &[PathBuf]) -> bool {
+    protected.iter().any(|p| path.starts_with(p))
+}
+
+/// Whether a folder last modified at `modified_at` changed within `window`
+/// (unknown times are not recent; times in the future are)
+pub fn is_recently_modified(modified_at: Option<SystemTime>, window: Option<Duration>) -> bool {
+    let (Some(modified), Some(window)) = (modified_at, window) else {
+        return false;
+    };
+    SystemTime::now()
+        .duration_since(modified)
+        .map_or(true, |age| age < window)
+}
+
 /// Validate paths before deletion - safety checks
-pub fn validate_deletion(paths: &[PathBuf]) -> Result<()> {
+pub fn validate_deletion(
+    paths: &[PathBuf],
+    protected: &[PathBuf],
+    allow_files: bool,
+) -> Result<()> {
     // Forbidden system directories (platform-specific)
     #[cfg(target_os = "windows")]
     let forbidden: &[&str] = &[
@@ -53,6 +467,14 @@ pub fn validate_deletion(paths: &[PathBuf]) -> Result<()> {
             }
         }
 
+        if let Some(guard) = protected.iter().find(|p| path.starts_with(p)) {
+            anyhow::bail!(
+                "Refusing to delete protected folder: {} (protected by {})",
+                path_str,
+                guard.display()
+            );
+        }
+
         // Verify it's actually a .claude folder
         if path.file_name() != Some(OsStr::new(".claude")) {
             anyhow::bail!("Not a .claude folder: {}", path_str);
@@ -63,8 +485,9 @@ pub fn validate_deletion(paths: &[PathBuf]) -> Result<()> {
             anyhow::bail!("Path does not exist: {}", path_str);
         }
 
-        // Verify it's a directory
-        if !path.is_dir() {
+        // Verify it's a directory (or a stray `.claude` file with --include-files)
+        let deletable = path.is_dir() || (allow_files && path.is_file());
+        if !deletable {
             anyhow::bail!("Path is not a directory: {}", path_str);
         }
     }
@@ -84,17 +507,49 @@ mod tests {
         let claude_path = temp.path().join(".claude");
         fs::create_dir(&claude_path).unwrap();
 
-        let result = validate_deletion(&[claude_path]);
+        let result = validate_deletion(&[claude_path], &[], false);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_deletion_rejects_protected_paths() {
+        let temp = tempdir().unwrap();
+        let project = temp.path().join("client");
+        let claude_path = project.join(".claude");
+        fs::create_dir_all(&claude_path).unwrap();
+
+        let result = validate_deletion(
+            std::slice::from_ref(&claude_path),
+            std::slice::from_ref(&project),
+            false,
+        );
+        assert!(result.unwrap_err().to_string().contains("protected folder"));
+
+        // Sibling names sharing a prefix are not protected
+        let other = temp.path().join("client-old");
+        assert!(!is_protected(&other.join(".claude"), &[project]));
+    }
+
+    #[test]
+    fn test_is_recently_modified() {
+        let hour = Duration::from_secs(60 * 60);
+        let day = Some(24 * hour);
+        let now = SystemTime::now();
+
+        assert!(is_recently_modified(Some(now - hour), day));
+        assert!(is_recently_modified(Some(now + hour), day));
+        assert!(!is_recently_modified(Some(now - 48 * hour), day));
+        assert!(!is_recently_modified(None, day));
+        assert!(!is_recently_modified(Some(now), None));
+    }
+
     #[test]
     fn test_validate_deletion_rejects_non_claude_folder() {
         let temp = tempdir().unwrap();
         let other_path = temp.path().join("other");
         fs::create_dir(&other_path).unwrap();
 
-        let result = validate_deletion(&[other_path]);
+        let result = validate_deletion(&[other_path], &[], false);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -105,14 +560,14 @@ mod tests {
     #[test]
     #[cfg(not(target_os = "windows"))]
     fn test_validate_deletion_rejects_system_paths() {
-        let result = validate_deletion(&[PathBuf::from("/Users")]);
+        let result = validate_deletion(&[PathBuf::from("/Users")], &[], false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("system directory"));
     }
 
     #[test]
     fn test_validate_deletion_rejects_nonexistent() {
-        let result = validate_deletion(&[PathBuf::from("/nonexistent/.claude")]);
+        let result = validate_deletion(&[PathBuf::from("/nonexistent/.claude")], &[], false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("does not exist"));
     }
@@ -123,10 +578,66 @@ mod tests {
         let claude_path = temp.path().join(".claude");
         fs::create_dir(&claude_path).unwrap();
         fs::write(claude_path.join("test.txt"), "test").unwrap();
+        fs::create_dir(claude_path.join("sub")).unwrap();
+        fs::write(claude_path.join("sub/more.txt"), "more").unwrap();
 
-        let result = permanent_delete(&[claude_path.clone()]);
+        let mut updates = Vec::new();
+        let result = permanent_delete(
+            std::slice::from_ref(&claude_path),
+            &AtomicBool::new(false),
+            &mut |p| updates.push(p),
+        );
         assert!(result.is_ok());
         assert!(!claude_path.exists());
+
+        // One update per top-level entry, then one for the finished folder
+        assert_eq!(updates.len(), 3);
+        assert_eq!(
+            updates.last(),
+            Some(&DeleteProgress {
+                folders: 1,
+                files: 2,
+                bytes: 8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_permanent_delete_cancelled() {
+        let temp = tempdir().unwrap();
+        let claude_path = temp.path().join(".claude");
+        fs::create_dir_all(claude_path.join("sub")).unwrap();
+
+        let cancel = AtomicBool::new(true);
+        let result = permanent_delete(std::slice::from_ref(&claude_path), &cancel, &mut |_| {});
+        assert!(format!("{:#}", result.unwrap_err()).contains("cancelled"));
+        assert!(claude_path.join("sub").exists());
+    }
+
+    #[test]
+    fn test_stray_claude_files() {
+        let temp = tempdir().unwrap();
+        let file = temp.path().join(".claude");
+        fs::write(&file, "stray").unwrap();
+
+        // Only accepted when files were asked for
+        let result = validate_deletion(std::slice::from_ref(&file), &[], false);
+        assert!(result.unwrap_err().to_string().contains("not a directory"));
+        validate_deletion(std::slice::from_ref(&file), &[], true).unwrap();
+
+        let archives =
+            backup_folders(std::slice::from_ref(&file), &temp.path().join("bk")).unwrap();
+        assert!(archives[0].exists());
+
+        let mut last = DeleteProgress::default();
+        permanent_delete(
+            std::slice::from_ref(&file),
+            &AtomicBool::new(false),
+            &mut |p| last = p,
+        )
+        .unwrap();
+        assert!(!file.exists());
+        assert_eq!((last.folders, last.files, last.bytes), (1, 1, 5));
     }
 
     #[test]
@@ -136,15 +647,168 @@ mod tests {
         fs::create_dir(&claude_path).unwrap();
         fs::write(claude_path.join("test.txt"), "test").unwrap();
 
-        let result = move_to_trash(&[claude_path.clone()]);
+        let result = move_to_trash(
+            std::slice::from_ref(&claude_path),
+            &AtomicBool::new(false),
+            &mut |_| {},
+        );
         assert!(result.is_ok());
         assert!(!claude_path.exists());
     }
 
+    #[test]
+    fn test_backup_folders() {
+        let temp = tempdir().unwrap();
+        let claude_path = temp.path().join("app/.claude");
+        fs::create_dir_all(claude_path.join("logs")).unwrap();
+        fs::write(claude_path.join("settings.json"), "{}").unwrap();
+        fs::write(claude_path.join("logs/a.log"), "log").unwrap();
+
+        let backups = temp.path().join("backups");
+        let archives =
+            backup_folders(&[claude_path.clone(), claude_path.clone()], &backups).unwrap();
+        assert_eq!(archives.len(), 2);
+        // Same folder twice in the same second still gets two archives
+        assert_ne!(archives[0], archives[1]);
+        assert!(archives[0].to_string_lossy().ends_with(".tar.gz"));
+
+        let file = fs::File::open(&archives[0]).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert!(names.contains(&".claude/settings.json".to_string()));
+        assert!(names.contains(&".claude/logs/a.log".to_string()));
+
+        // Restoring refuses to overwrite, then brings the folder back
+        assert!(restore_from_backup(&archives[0], &claude_path).is_err());
+        fs::remove_dir_all(&claude_path).unwrap();
+        restore_from_backup(&archives[0], &claude_path).unwrap();
+        assert_eq!(
+            fs::read_to_string(claude_path.join("logs/a.log")).unwrap(),
+            "log"
+        );
+        assert!(restore_from_backup(&backups.join("missing.tar.gz"), &claude_path).is_err());
+    }
+
+    #[test]
+    fn test_quarantine_and_restore() {
+        let temp = tempdir().unwrap();
+        let quarantine = temp.path().join("quarantine");
+        let claude_path = temp.path().join("project/.claude");
+        fs::create_dir_all(&claude_path).unwrap();
+        fs::write(claude_path.join("test.txt"), "test").unwrap();
+
+        let mut moved = Vec::new();
+        move_to_quarantine(
+            std::slice::from_ref(&claude_path),
+            &quarantine,
+            &mut moved,
+            &AtomicBool::new(false),
+            &mut |_| {},
+        )
+        .unwrap();
+        assert_eq!(moved.len(), 1);
+        assert!(!claude_path.exists());
+        assert!(moved[0].starts_with(&quarantine));
+        assert!(moved[0].join("test.txt").exists());
+
+        restore_from_quarantine(&moved[0], &claude_path).unwrap();
+        assert!(claude_path.join("test.txt").exists());
+        assert!(!moved[0].exists());
+    }
+
+    #[test]
+    fn test_quarantine_name_collisions() {
+        let temp = tempdir().unwrap();
+        let quarantine = temp.path().join("quarantine");
+        let a = temp.path().join("a/.claude");
+        let b = temp.path().join("b/.claude");
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        fs::write(a.join("first.txt"), "1").unwrap();
+
+        // Different projects land in different places
+        let mut moved = Vec::new();
+        move_to_quarantine(
+            &[a.clone(), b.clone()],
+            &quarantine,
+            &mut moved,
+            &AtomicBool::new(false),
+            &mut |_| {},
+        )
+        .unwrap();
+        assert_ne!(moved[0], moved[1]);
+
+        // The same folder quarantined twice gets a distinct name
+        fs::create_dir_all(&a).unwrap();
+        fs::write(a.join("second.txt"), "2").unwrap();
+        let mut again = Vec::new();
+        move_to_quarantine(
+            std::slice::from_ref(&a),
+            &quarantine,
+            &mut again,
+            &AtomicBool::new(false),
+            &mut |_| {},
+        )
+        .unwrap();
+        assert_ne!(again[0], moved[0]);
+        assert!(moved[0].join("first.txt").exists());
+        assert!(again[0].join("second.txt").exists());
+
+        // Restoring refuses to overwrite an existing folder
+        restore_from_quarantine(&again[0], &a).unwrap();
+        assert!(a.join("second.txt").exists());
+        assert!(restore_from_quarantine(&moved[0], &a).is_err());
+    }
+
+    #[test]
+    fn test_quarantine_reports_partial_moves() {
+        let temp = tempdir().unwrap();
+        let quarantine = temp.path().join("quarantine");
+        let a = temp.path().join("a/.claude");
+        fs::create_dir_all(&a).unwrap();
+
+        // The first folder is moved before the missing one fails
+        let mut moved = Vec::new();
+        let missing = temp.path().join("gone/.claude");
+        assert!(move_to_quarantine(
+            &[a.clone(), missing],
+            &quarantine,
+            &mut moved,
+            &AtomicBool::new(false),
+            &mut |_| {}
+        )
+        .is_err());
+        assert_eq!(moved.len(), 1);
+        assert!(!a.exists());
+        assert!(moved[0].exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_copy_dir_keeps_symlinks() {
+        let temp = tempdir().unwrap();
+        let from = temp.path().join("from");
+        fs::create_dir_all(from.join("sub")).unwrap();
+        fs::write(from.join("sub/file.txt"), "x").unwrap();
+        std::os::unix::fs::symlink("sub/file.txt", from.join("link")).unwrap();
+
+        let to = temp.path().join("to");
+        copy_dir_all(&from, &to).unwrap();
+        assert_eq!(fs::read_to_string(to.join("sub/file.txt")).unwrap(), "x");
+        assert_eq!(
+            fs::read_link(to.join("link")).unwrap(),
+            PathBuf::from("sub/file.txt")
+        );
+    }
+
     #[test]
     #[cfg(target_os = "windows")]
     fn test_validate_deletion_rejects_windows_system_paths() {
-        let result = validate_deletion(&[PathBuf::from("C:\\Users")]);
+        let result = validate_deletion(&[PathBuf::from("C:\\Users")], &[], false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("system directory"));
     }
@@ -152,7 +816,7 @@ mod tests {
     #[test]
     #[cfg(target_os = "windows")]
     fn test_validate_deletion_rejects_windows_root() {
-        let result = validate_deletion(&[PathBuf::from("C:\\")]);
+        let result = validate_deletion(&[PathBuf::from("C:\\")], &[], false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("system directory"));
     }
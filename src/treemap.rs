@@ -0,0 +1,135 @@
+//! Squarified treemap layout (Bruls, Huizing & van Wijk) for the HTML report
+
+/// An axis-aligned rectangle in output units (SVG pixels)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+}
+
+/// Lay out `values` (largest first) as rectangles filling `bounds`, with
+/// areas proportional to the values. Returns one rectangle per value, in
+/// input order; zero values get an empty rectangle
+pub fn squarify(values: &[u64], bounds: Rect) -> Vec<Rect> {
+    let total: u64 = values.iter().sum();
+    let mut rects = vec![
+        Rect {
+            x: bounds.x,
+            y: bounds.y,
+            w: 0.0,
+            h: 0.0,
+        };
+        values.len()
+    ];
+    if total == 0 || bounds.w <= 0.0 || bounds.h <= 0.0 {
+        return rects;
+    }
+
+    // Scale values to areas
+    let scale = bounds.w * bounds.h / total as f64;
+    let areas: Vec<f64> = values.iter().map(|&v| v as f64 * scale).collect();
+
+    let mut free = bounds;
+    let mut start = 0;
+    while start < areas.len() && areas[start] > 0.0 {
+        let side = free.w.min(free.h);
+
+        // Grow the row while it keeps the worst aspect ratio from getting worse
+        let mut end = start + 1;
+        while end < areas.len()
+            && areas[end] > 0.0
+            && worst_ratio(&areas[start..=end], side) <= worst_ratio(&areas[start..end], side)
+        {
+            end += 1;
+        }
+
+        // Place the row along the shorter side of the free space
+        let row_area: f64 = areas[start..end].iter().sum();
+        let thickness = row_area / side;
+        let mut offset = 0.0;
+        for (rect, &area) in rects[start..end].iter_mut().zip(&areas[start..end]) {
+            let length = area / thickness;
+            *rect = if free.w >= free.h {
+                Rect {
+                    x: free.x,
+                    y: free.y + offset,
+                    w: thickness,
+                    h: length,
+                }
+            } else {
+                Rect {
+                    x: free.x + offset,
+                    y: free.y,
+                    w: length,
+                    h: thickness,
+                }
+            };
+            offset += length;
+        }
+
+        if free.w >= free.h {
+            free.x += thickness;
+            free.w -= thickness;
+        } else {
+            free.y += thickness;
+            free.h -= thickness;
+        }
+        start = end;
+    }
+
+    rects
+}
+
+/// Worst aspect ratio of a row of areas laid along a side of length `side`
+fn worst_ratio(row: &[f64], side: f64) -> f64 {
+    let sum: f64 = row.iter().sum();
+    let max = row.iter().cloned().fold(f64::MIN, f64::max);
+    let min = row.iter().cloned().fold(f64::MAX, f64::min);
+    let side2 = side * side;
+    let sum2 = sum * sum;
+    (side2 * max / sum2).max(sum2 / (side2 * min))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_squarify_fills_bounds() {
+        let bounds = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 600.0,
+            h: 400.0,
+        };
+        let values = [6, 6, 4, 3, 2, 2, 1];
+        let rects = squarify(&values, bounds);
+        assert_eq!(rects.len(), values.len());
+
+        // Areas are proportional and everything stays inside the bounds
+        let unit = bounds.w * bounds.h / 24.0;
+        for (rect, &value) in rects.iter().zip(&values) {
+            assert!((rect.w * rect.h - value as f64 * unit).abs() < 1e-6);
+            assert!(rect.x >= -1e-9 && rect.y >= -1e-9);
+            assert!(rect.x + rect.w <= bounds.w + 1e-9);
+            assert!(rect.y + rect.h <= bounds.h + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_squarify_zero_values() {
+        let bounds = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 100.0,
+            h: 100.0,
+        };
+        assert!(squarify(&[0, 0], bounds).iter().all(|r| r.w * r.h == 0.0));
+
+        let rects = squarify(&[10, 0], bounds);
+        assert_eq!((rects[0].w, rects[0].h), (100.0, 100.0));
+        assert_eq!(rects[1].w * rects[1].h, 0.0);
+    }
+}
@@ -4,10 +4,12 @@ use anyhow::Result;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
 };
 use ratatui::prelude::*;
-use std::io::{stdout, Stdout};
+use std::io::{stdout, Stdout, Write};
 
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
@@ -26,3 +28,25 @@ pub fn restore() -> Result<()> {
     execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
     Ok(())
 }
+
+/// Save the current window title on the terminal's title stack (XTWINOPS)
+pub fn push_title() -> Result<()> {
+    let mut out = stdout();
+    write!(out, "\x1b[22;0t")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Restore the window title saved by `push_title`
+pub fn pop_title() -> Result<()> {
+    let mut out = stdout();
+    write!(out, "\x1b[23;0t")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Set the terminal window/tab title
+pub fn set_title(title: &str) -> Result<()> {
+    execute!(stdout(), SetTitle(title))?;
+    Ok(())
+}
@@ -1,32 +1,93 @@
 // Keybinds module - keyboard input handling
 
+use super::keymap::KeyAction;
 use crate::app::{App, AppState, InputMode};
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use std::time::Duration;
 
 /// Actions that can be triggered by user input
+#[derive(Debug, PartialEq, Eq)]
 pub enum Action {
     None,
     Quit,
     Delete,
+    CancelScan,
+    Rescan,
 }
 
 /// Handle keyboard events with timeout
 pub fn handle_events(app: &mut App, timeout: Duration) -> anyhow::Result<Action> {
     if event::poll(timeout)? {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                // Handle search mode separately
-                if app.input_mode == InputMode::Search {
-                    return handle_search_input(app, key.code);
-                }
-                return handle_key(app, key.code, key.modifiers);
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                return dispatch_key(app, key.code, key.modifiers);
             }
+            Event::Mouse(mouse) => handle_mouse(app, mouse),
+            _ => {}
+        }
+    }
+    Ok(Action::None)
+}
+
+/// Route a key press to the handler for the current input mode
+fn dispatch_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> anyhow::Result<Action> {
+    // Ctrl/Alt chords are commands, never text
+    let text_code = match code {
+        KeyCode::Char(_) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            KeyCode::Null
+        }
+        _ => code,
+    };
+
+    match app.input_mode {
+        InputMode::Search => handle_search_input(app, text_code),
+        InputMode::MinSize => handle_min_size_input(app, text_code),
+        InputMode::Normal => handle_key(app, code, modifiers),
+    }
+}
+
+/// Handle input in the minimum size prompt
+fn handle_min_size_input(app: &mut App, code: KeyCode) -> anyhow::Result<Action> {
+    match code {
+        KeyCode::Esc => app.exit_min_size_mode(),
+        KeyCode::Enter => app.apply_min_size(),
+        KeyCode::Backspace => {
+            app.size_input.pop();
         }
+        KeyCode::Char(c) => app.size_input.push(c),
+        _ => {}
     }
     Ok(Action::None)
 }
 
+/// Handle mouse clicks and scrolling in the folder list
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    if app.show_help
+        || app.show_details
+        || app.state != AppState::Browsing
+        || app.input_mode != InputMode::Normal
+    {
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(row) = app.list_row_at(mouse.column, mouse.row) {
+                app.selected_index = row;
+                // The selection marker sits just inside the left border
+                if mouse.column <= app.list_area.x + 2 {
+                    app.toggle_selection();
+                }
+            }
+        }
+        MouseEventKind::ScrollUp => app.move_up(),
+        MouseEventKind::ScrollDown => app.move_down(),
+        _ => {}
+    }
+}
+
 /// Handle input in search mode
 fn handle_search_input(app: &mut App, code: KeyCode) -> anyhow::Result<Action> {
     match code {
@@ -38,9 +99,11 @@ fn handle_search_input(app: &mut App, code: KeyCode) -> anyhow::Result<Action> {
         }
         KeyCode::Backspace => {
             app.search_input.pop();
+            app.update_search();
         }
         KeyCode::Char(c) => {
             app.search_input.push(c);
+            app.update_search();
         }
         _ => {}
     }
@@ -48,71 +111,633 @@ fn handle_search_input(app: &mut App, code: KeyCode) -> anyhow::Result<Action> {
 }
 
 fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> anyhow::Result<Action> {
-    // Handle help overlay first - any key closes it
+    // Handle help overlay first - it scrolls, and only ?/Esc/q close it
     if app.show_help {
-        app.show_help = false;
+        let max_scroll = super::render::help_max_scroll(&app.keymap, app.screen_height);
+        match code {
+            KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::Esc => app.show_help = false,
+            KeyCode::Up | KeyCode::Char('k') => app.help_scroll = app.help_scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.help_scroll = (app.help_scroll + 1).min(max_scroll)
+            }
+            KeyCode::PageUp => app.help_scroll = app.help_scroll.saturating_sub(10),
+            KeyCode::PageDown => app.help_scroll = (app.help_scroll + 10).min(max_scroll),
+            KeyCode::Home | KeyCode::Char('g') => app.help_scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => app.help_scroll = max_scroll,
+            _ => {}
+        }
+        return Ok(Action::None);
+    }
+
+    // Detail pane: the cursor still moves so the pane can follow it
+    if app.show_details {
+        match code {
+            KeyCode::Enter | KeyCode::Char('q') | KeyCode::Esc => app.show_details = false,
+            KeyCode::Up | KeyCode::Char('k') => app.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => app.move_down(),
+            _ => {}
+        }
+        return Ok(Action::None);
+    }
+
+    // Keys wait until a running deletion finishes; Ctrl+C stops it and quits
+    if app.state == AppState::Deleting {
+        if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+            app.should_quit = true;
+            return Ok(Action::Quit);
+        }
         return Ok(Action::None);
     }
 
     // Handle confirm dialog
     if app.state == AppState::Confirming {
         match code {
+            // Permanent deletes wait out the countdown
+            KeyCode::Char('y') | KeyCode::Char('Y') if app.confirm_countdown().is_some() => {}
+            // Recently modified folders take a second 'y'
+            KeyCode::Char('y') | KeyCode::Char('Y')
+                if app.recent_target_count() > 0 && !app.recent_confirmed =>
+            {
+                app.recent_confirmed = true;
+            }
             KeyCode::Char('y') | KeyCode::Char('Y') => {
+                app.confirm_preview = None;
+                app.recent_confirmed = false;
                 app.state = AppState::Deleting;
                 return Ok(Action::Delete);
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.confirm_preview = None;
+                app.recent_confirmed = false;
+                app.grown_since_scan = None;
+                app.delete_target = None;
                 app.state = AppState::Browsing;
                 app.message = None;
             }
+            KeyCode::Char('p') => app.toggle_confirm_preview(),
             _ => {}
         }
         return Ok(Action::None);
     }
 
-    // Normal keybinds
-    match code {
-        // Quit
-        KeyCode::Char('q') | KeyCode::Esc => {
-            app.should_quit = true;
-            return Ok(Action::Quit);
+    // Esc stops an in-progress scan instead of quitting
+    if app.state == AppState::Scanning && code == KeyCode::Esc {
+        return Ok(Action::CancelScan);
+    }
+
+    // Ctrl+C always quits, whatever the keymap says
+    if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+        app.should_quit = true;
+        return Ok(Action::Quit);
+    }
+
+    // Vim-style counts: unbound digits accumulate until the next action
+    if let KeyCode::Char(c @ '0'..='9') = code {
+        if app.keymap.action(code, modifiers).is_none() && (c != '0' || app.pending_count.is_some())
+        {
+            let digit = c as usize - '0' as usize;
+            let count = app.pending_count.unwrap_or(0).saturating_mul(10) + digit;
+            app.pending_count = Some(count.min(99_999));
+            return Ok(Action::None);
         }
-        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+    }
+
+    // Esc drops a pending count instead of quitting
+    let count = app.pending_count.take();
+    if code == KeyCode::Esc && count.is_some() {
+        return Ok(Action::None);
+    }
+    let times = count.unwrap_or(1);
+
+    // Normal keybinds (configurable via [keybinds])
+    let Some(action) = app.keymap.action(code, modifiers) else {
+        return Ok(Action::None);
+    };
+    match action {
+        // Quit
+        KeyAction::Quit => {
             app.should_quit = true;
             return Ok(Action::Quit);
         }
 
         // Navigation
-        KeyCode::Up | KeyCode::Char('k') => app.move_up(),
-        KeyCode::Down | KeyCode::Char('j') => app.move_down(),
-        KeyCode::PageUp => app.page_up(10),
-        KeyCode::PageDown => app.page_down(10),
-        KeyCode::Home | KeyCode::Char('g') => app.go_home(),
-        KeyCode::End | KeyCode::Char('G') => app.go_end(),
+        KeyAction::Up => app.page_up(times),
+        KeyAction::Down => app.page_down(times),
+        KeyAction::PageUp => app.page_up(10 * times),
+        KeyAction::PageDown => app.page_down(10 * times),
+        // With a count, `5g`/`5G` jump to the 5th row
+        KeyAction::Top | KeyAction::Bottom if count.is_some() => app.go_to(times - 1),
+        KeyAction::Top => app.go_home(),
+        KeyAction::Bottom => app.go_end(),
+        KeyAction::NextMatch => app.jump_to_match(true),
+        KeyAction::PrevMatch => app.jump_to_match(false),
 
         // Selection
-        KeyCode::Char(' ') => app.toggle_selection(),
-        KeyCode::Char('a') => app.select_all(),
-        KeyCode::Char('n') => app.select_none(),
+        KeyAction::Toggle => app.toggle_selection(),
+        KeyAction::SelectAll => app.select_all(),
+        KeyAction::SelectNone => app.select_none(),
+        KeyAction::SelectNoneVisible => app.select_none_visible(),
+        KeyAction::InvertSelection => app.invert_selection(),
+        KeyAction::OpenFolder => app.open_highlighted(),
+        KeyAction::CopyPath => app.copy_highlighted_path(),
 
         // Delete
-        KeyCode::Char('d') => {
-            if app.selected_count() > 0 && app.state == AppState::Browsing {
-                app.state = AppState::Confirming;
-            }
+        KeyAction::Delete if app.selected_count() > 0 && app.state == AppState::Browsing => {
+            return Ok(start_delete(app));
+        }
+        KeyAction::Delete => {}
+        KeyAction::DeleteHighlighted
+            if app.state == AppState::Browsing && app.target_highlighted() =>
+        {
+            return Ok(start_delete(app));
         }
+        KeyAction::DeleteHighlighted => {}
+
+        // Toggle trash/permanent deletion mode
+        KeyAction::TogglePermanent => app.toggle_permanent_delete(),
 
         // Help
-        KeyCode::Char('?') => app.show_help = !app.show_help,
+        KeyAction::Help => {
+            app.show_help = !app.show_help;
+            app.help_scroll = 0;
+        }
+        KeyAction::Details => app.show_details = app.highlighted_folder().is_some(),
 
         // Search & Filter
-        KeyCode::Char('/') => app.enter_search_mode(),
-        KeyCode::Char('F') => app.toggle_filter_bar(),
-        KeyCode::Char('s') => app.cycle_sort(),
-        KeyCode::Char('c') => app.clear_filters(),
+        KeyAction::Search => app.enter_search_mode(),
+        KeyAction::FilterBar => app.toggle_filter_bar(),
+        KeyAction::Sort => app.cycle_sort(),
+        KeyAction::MinSize => app.enter_min_size_mode(),
+        KeyAction::TypeFilter => app.cycle_project_type(),
+        KeyAction::Fuzzy => app.toggle_fuzzy_search(),
+        KeyAction::ClearFilters => app.clear_filters(),
 
-        _ => {}
+        // Re-scan (one scan at a time)
+        KeyAction::Rescan if app.scan_complete && app.state == AppState::Browsing => {
+            return Ok(Action::Rescan);
+        }
+        KeyAction::Rescan => {
+            if !app.scan_complete {
+                app.message = Some("A scan is already running".to_string());
+            }
+        }
     }
 
     Ok(Action::None)
 }
+
+/// Confirm the deletion if needed, otherwise start it right away
+fn start_delete(app: &mut App) -> Action {
+    if app.needs_confirmation() {
+        app.open_confirm();
+        Action::None
+    } else {
+        app.state = AppState::Deleting;
+        Action::Delete
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::ClaudeFolder;
+    use std::path::PathBuf;
+
+    fn browsing_app(paths: &[&str]) -> App {
+        let mut app = App::new(false);
+        for path in paths {
+            app.add_folder(ClaudeFolder {
+                path: PathBuf::from(path),
+                ..Default::default()
+            });
+        }
+        app.complete_scan();
+        app
+    }
+
+    /// Feed key presses as if typed, returning the action of the last one
+    fn type_keys(app: &mut App, codes: &[KeyCode]) -> Action {
+        let mut action = Action::None;
+        for &code in codes {
+            action = dispatch_key(app, code, KeyModifiers::NONE).unwrap();
+        }
+        action
+    }
+
+    /// A key sequence from a string: `<CR>` is Enter, `<Esc>` is Esc
+    fn keys(script: &str) -> Vec<KeyCode> {
+        let mut codes = Vec::new();
+        let mut rest = script;
+        while let Some(c) = rest.chars().next() {
+            let (code, len) = if rest.starts_with("<CR>") {
+                (KeyCode::Enter, 4)
+            } else if rest.starts_with("<Esc>") {
+                (KeyCode::Esc, 5)
+            } else {
+                (KeyCode::Char(c), c.len_utf8())
+            };
+            codes.push(code);
+            rest = &rest[len..];
+        }
+        codes
+    }
+
+    fn click(app: &mut App, column: u16, row: u16) {
+        handle_mouse(
+            app,
+            MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            },
+        );
+    }
+
+    #[test]
+    fn test_scripted_session() {
+        let mut app = browsing_app(&["/p/web-app/.claude", "/p/cli/.claude", "/p/web-api/.claude"]);
+
+        // Search, then select everything that matches
+        type_keys(&mut app, &keys("/web<CR>a"));
+        assert_eq!(app.filter.search_query(), Some("web"));
+        assert_eq!(app.visible_count(), 2);
+        assert_eq!(app.selected_count(), 2);
+
+        // Backing out of the confirm dialog keeps the selection
+        type_keys(&mut app, &keys("dn"));
+        assert_eq!(app.state, AppState::Browsing);
+        assert_eq!(app.selected_count(), 2);
+
+        // Deselect the highlighted folder, re-sort, then delete the rest
+        let initial = app.sort_order;
+        type_keys(&mut app, &keys(" s"));
+        assert_eq!(app.selected_count(), 1);
+        assert_eq!(app.sort_order, initial.next());
+
+        let action = type_keys(&mut app, &keys("dy"));
+        assert!(matches!(action, Action::Delete));
+        assert_eq!(app.state, AppState::Deleting);
+        assert_eq!(app.get_selected_folders().len(), 1);
+
+        // Input is ignored until the deletion finishes
+        assert!(matches!(type_keys(&mut app, &keys("q")), Action::None));
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_mouse_click_and_scroll() {
+        let mut app = browsing_app(&["/p/a/.claude", "/p/b/.claude", "/p/c/.claude"]);
+        app.list_area = ratatui::layout::Rect::new(0, 6, 80, 10);
+
+        // Row 7 is the first item below the top border
+        click(&mut app, 20, 9);
+        assert_eq!(app.selected_index, 2);
+        assert!(!app.folders.iter().any(|f| f.selected));
+
+        click(&mut app, 1, 8);
+        assert_eq!(app.selected_index, 1);
+        assert_eq!(app.selected_count(), 1);
+
+        // Clicks on the border or past the last item are ignored
+        click(&mut app, 20, 6);
+        click(&mut app, 20, 12);
+        assert_eq!(app.selected_index, 1);
+
+        // Rows are relative to the scrolled viewport
+        *app.list_state.offset_mut() = 1;
+        click(&mut app, 20, 8);
+        assert_eq!(app.selected_index, 2);
+
+        handle_mouse(
+            &mut app,
+            MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                column: 20,
+                row: 8,
+                modifiers: KeyModifiers::NONE,
+            },
+        );
+        assert_eq!(app.selected_index, 1);
+    }
+
+    #[test]
+    fn test_sort_filter_and_clear_keys() {
+        let mut app = browsing_app(&["/p/web/.claude"]);
+        let initial = app.sort_order;
+
+        type_keys(&mut app, &[KeyCode::Char('s')]);
+        assert_eq!(app.sort_order, initial.next());
+
+        type_keys(&mut app, &[KeyCode::Char('F')]);
+        assert!(app.show_filter_bar);
+
+        app.filter.set_search(Some("web".to_string()));
+        type_keys(&mut app, &[KeyCode::Char('c')]);
+        assert!(app.filter.search_query().is_none());
+        assert!(!app.should_quit);
+
+        handle_key(&mut app, KeyCode::Char('c'), KeyModifiers::CONTROL).unwrap();
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_ctrl_c_stops_deletion() {
+        let mut app = browsing_app(&["/p/web/.claude"]);
+        app.state = AppState::Deleting;
+
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            handle_key(&mut app, KeyCode::Char('q'), none).unwrap(),
+            Action::None
+        );
+        assert!(!app.should_quit);
+        assert_eq!(
+            handle_key(&mut app, KeyCode::Char('c'), KeyModifiers::CONTROL).unwrap(),
+            Action::Quit
+        );
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_recent_folders_need_second_confirmation() {
+        let mut app = browsing_app(&["/p/a/.claude", "/p/b/.claude"]);
+        app.confirm_delete = false;
+        app.recent_window = Some(Duration::from_secs(3600));
+        app.folders[0].modified_at = Some(std::time::SystemTime::now());
+
+        // Only old folders selected: deleted without asking
+        app.folders[1].selected = true;
+        let action = dispatch_key(&mut app, KeyCode::Char('d'), KeyModifiers::NONE).unwrap();
+        assert!(matches!(action, Action::Delete));
+
+        app.state = AppState::Browsing;
+        app.folders[0].selected = true;
+        type_keys(&mut app, &[KeyCode::Char('d'), KeyCode::Char('y')]);
+        assert_eq!(app.state, AppState::Confirming);
+        assert!(app.recent_confirmed);
+
+        let action = dispatch_key(&mut app, KeyCode::Char('y'), KeyModifiers::NONE).unwrap();
+        assert!(matches!(action, Action::Delete));
+        assert!(!app.recent_confirmed);
+    }
+
+    #[test]
+    fn test_permanent_delete_countdown() {
+        let mut app = browsing_app(&["/p/a/.claude"]);
+        app.permanent_confirm_delay = Duration::from_secs(60);
+        app.folders[0].selected = true;
+
+        // Trash deletes aren't delayed
+        type_keys(&mut app, &[KeyCode::Char('d')]);
+        assert_eq!(app.confirm_countdown(), None);
+        app.state = AppState::Browsing;
+
+        app.permanent_delete = true;
+        type_keys(&mut app, &[KeyCode::Char('d'), KeyCode::Char('y')]);
+        assert_eq!(app.state, AppState::Confirming);
+        assert!(app.confirm_countdown().is_some());
+
+        // Once the delay has passed, y goes through
+        app.permanent_confirm_delay = Duration::ZERO;
+        let action = dispatch_key(&mut app, KeyCode::Char('y'), KeyModifiers::NONE).unwrap();
+        assert!(matches!(action, Action::Delete));
+    }
+
+    #[test]
+    fn test_selection_chords() {
+        let mut app = browsing_app(&["/p/a/.claude", "/p/b/.claude"]);
+
+        handle_key(&mut app, KeyCode::Char('a'), KeyModifiers::CONTROL).unwrap();
+        assert_eq!(app.selected_count(), 2);
+        handle_key(&mut app, KeyCode::Char('d'), KeyModifiers::CONTROL).unwrap();
+        assert_eq!(app.selected_count(), 0);
+        type_keys(&mut app, &[KeyCode::Char('*')]);
+        assert_eq!(app.selected_count(), 2);
+        type_keys(&mut app, &[KeyCode::Char('n')]);
+
+        // Selection keys are text while searching, and chords are dropped
+        type_keys(
+            &mut app,
+            &[KeyCode::Char('/'), KeyCode::Char('a'), KeyCode::Char('n')],
+        );
+        dispatch_key(&mut app, KeyCode::Char('a'), KeyModifiers::CONTROL).unwrap();
+        assert_eq!(app.search_input, "an");
+        assert_eq!(app.selected_count(), 0);
+    }
+
+    #[test]
+    fn test_help_scrolls_and_closes() {
+        let mut app = browsing_app(&["/p/a/.claude"]);
+        type_keys(&mut app, &[KeyCode::Char('?')]);
+        assert!(app.show_help);
+
+        // Scrolling and other keys leave the overlay open
+        type_keys(
+            &mut app,
+            &[
+                KeyCode::Down,
+                KeyCode::Down,
+                KeyCode::Up,
+                KeyCode::Char('d'),
+            ],
+        );
+        assert!(app.show_help);
+        assert_eq!(app.help_scroll, 1);
+        assert_eq!(app.state, AppState::Browsing);
+
+        type_keys(&mut app, &[KeyCode::Esc]);
+        assert!(!app.show_help);
+        assert!(!app.should_quit);
+
+        type_keys(&mut app, &[KeyCode::Char('?')]);
+        assert_eq!(app.help_scroll, 0);
+        type_keys(&mut app, &[KeyCode::Char('?')]);
+        assert!(!app.show_help);
+    }
+
+    #[test]
+    fn test_help_scroll_stops_at_the_end() {
+        let mut app = browsing_app(&["/p/a/.claude"]);
+        app.screen_height = 20;
+        let max_scroll = super::super::render::help_max_scroll(&app.keymap, 20);
+        assert!(max_scroll > 0);
+
+        type_keys(&mut app, &[KeyCode::Char('?'), KeyCode::End]);
+        assert_eq!(app.help_scroll, max_scroll);
+        type_keys(&mut app, &[KeyCode::Down, KeyCode::PageDown]);
+        assert_eq!(app.help_scroll, max_scroll);
+        // One step back up moves the view right away
+        type_keys(&mut app, &[KeyCode::Up]);
+        assert_eq!(app.help_scroll, max_scroll - 1);
+    }
+
+    #[test]
+    fn test_count_prefix() {
+        let paths: Vec<String> = (0..30).map(|i| format!("/p/{}/.claude", i)).collect();
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+        let mut app = browsing_app(&paths);
+
+        type_keys(&mut app, &[KeyCode::Char('5'), KeyCode::Char('j')]);
+        assert_eq!(app.selected_index, 5);
+        assert_eq!(app.pending_count, None);
+
+        // The count only applies once
+        type_keys(&mut app, &[KeyCode::Char('j')]);
+        assert_eq!(app.selected_index, 6);
+
+        type_keys(
+            &mut app,
+            &[KeyCode::Char('1'), KeyCode::Char('0'), KeyCode::Char('j')],
+        );
+        assert_eq!(app.selected_index, 16);
+        type_keys(&mut app, &[KeyCode::Char('3'), KeyCode::Char('k')]);
+        assert_eq!(app.selected_index, 13);
+
+        // 5G jumps to the 5th row; 0 alone isn't a count
+        type_keys(&mut app, &[KeyCode::Char('5'), KeyCode::Char('G')]);
+        assert_eq!(app.selected_index, 4);
+        type_keys(&mut app, &[KeyCode::Char('0'), KeyCode::Char('G')]);
+        assert_eq!(app.selected_index, 29);
+
+        // Esc cancels a count rather than quitting
+        type_keys(&mut app, &[KeyCode::Char('9'), KeyCode::Esc]);
+        assert!(!app.should_quit);
+        assert_eq!(app.pending_count, None);
+    }
+
+    #[test]
+    fn test_custom_keymap() {
+        let mut app = browsing_app(&["/p/a/.claude", "/p/b/.claude"]);
+        let config = [("delete".to_string(), "x".to_string())].into();
+        app.keymap = crate::ui::KeyMap::from_config(&config).0;
+
+        type_keys(&mut app, &[KeyCode::Char('a'), KeyCode::Char('d')]);
+        assert_eq!(app.state, AppState::Browsing);
+
+        type_keys(&mut app, &[KeyCode::Char('x')]);
+        assert_eq!(app.state, AppState::Confirming);
+    }
+
+    #[test]
+    fn test_min_size_prompt() {
+        let mut app = browsing_app(&["/p/web/.claude"]);
+        app.folders[0].size = 10 * 1024 * 1024;
+
+        type_keys(&mut app, &[KeyCode::Char('m')]);
+        assert_eq!(app.input_mode, InputMode::MinSize);
+        for c in "50MB".chars() {
+            type_keys(&mut app, &[KeyCode::Char(c)]);
+        }
+        type_keys(&mut app, &[KeyCode::Enter]);
+        assert_eq!(app.filter.min_size, Some(50 * 1024 * 1024));
+        assert_eq!(app.visible_count(), 0);
+
+        // Invalid input keeps the prompt open
+        type_keys(
+            &mut app,
+            &[KeyCode::Char('m'), KeyCode::Char('x'), KeyCode::Enter],
+        );
+        assert_eq!(app.input_mode, InputMode::MinSize);
+        type_keys(&mut app, &[KeyCode::Esc, KeyCode::Char('c')]);
+        assert_eq!(app.filter.min_size, None);
+        assert_eq!(app.visible_count(), 1);
+    }
+
+    #[test]
+    fn test_project_type_cycle() {
+        let mut app = browsing_app(&["/p/a/.claude", "/p/b/.claude", "/p/c/.claude"]);
+        app.folders[0].project_type = "Rust".to_string();
+        app.folders[1].project_type = "Node".to_string();
+        app.folders[2].project_type = "Rust".to_string();
+
+        type_keys(&mut app, &[KeyCode::Char('t')]);
+        assert_eq!(app.filter.project_types, vec!["Node".to_string()]);
+        assert_eq!(app.visible_count(), 1);
+
+        type_keys(&mut app, &[KeyCode::Char('t')]);
+        assert_eq!(app.filter.project_types, vec!["Rust".to_string()]);
+        assert_eq!(app.visible_count(), 2);
+
+        type_keys(&mut app, &[KeyCode::Char('t')]);
+        assert!(app.filter.project_types.is_empty());
+        assert_eq!(app.visible_count(), 3);
+    }
+
+    #[test]
+    fn test_delete_highlighted_only() {
+        let mut app = browsing_app(&["/p/a/.claude", "/p/b/.claude", "/p/c/.claude"]);
+        app.confirm_delete = true;
+        type_keys(&mut app, &keys(" j"));
+        let highlighted = app.folders[app.visible_folder_indices()[app.selected_index]]
+            .path
+            .clone();
+
+        type_keys(&mut app, &keys("D"));
+        assert_eq!(app.state, AppState::Confirming);
+        let targets = app.deletion_targets();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].path, highlighted);
+
+        // Cancelling brings the marked folder back as the selection
+        type_keys(&mut app, &keys("n"));
+        assert_eq!(app.delete_target, None);
+        assert_eq!(app.selected_count(), 1);
+        assert_ne!(app.get_selected_folders()[0].path, highlighted);
+
+        app.confirm_delete = false;
+        assert_eq!(type_keys(&mut app, &keys("D")), Action::Delete);
+        assert_eq!(app.deletion_targets()[0].path, highlighted);
+        // The selection itself is untouched
+        assert_ne!(app.get_selected_folders()[0].path, highlighted);
+    }
+
+    #[test]
+    fn test_rescan() {
+        let mut app = browsing_app(&["/p/web/.claude", "/p/api/.claude"]);
+        type_keys(&mut app, &keys("s/web<CR>"));
+        let sort_order = app.sort_order;
+
+        assert_eq!(type_keys(&mut app, &keys("r")), Action::Rescan);
+        app.restart_scan();
+        assert_eq!(app.state, AppState::Scanning);
+        assert!(app.folders.is_empty());
+        assert_eq!(app.sort_order, sort_order);
+        assert_eq!(app.filter.search_query(), Some("web"));
+
+        // No second scan while one is running
+        assert_eq!(type_keys(&mut app, &keys("r")), Action::None);
+        assert!(app.message.is_some());
+    }
+
+    #[test]
+    fn test_search_typing() {
+        let mut app = browsing_app(&["/p/web/.claude", "/p/api/.claude"]);
+
+        type_keys(
+            &mut app,
+            &[
+                KeyCode::Char('/'),
+                KeyCode::Char('a'),
+                KeyCode::Char('p'),
+                KeyCode::Char('x'),
+                KeyCode::Backspace,
+            ],
+        );
+        assert_eq!(app.input_mode, InputMode::Search);
+        assert_eq!(app.search_input, "ap");
+
+        type_keys(&mut app, &[KeyCode::Enter]);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.filter.search_query(), Some("ap"));
+        assert_eq!(app.visible_count(), 1);
+
+        // Typed 'q' goes into the query instead of quitting
+        type_keys(
+            &mut app,
+            &[KeyCode::Char('/'), KeyCode::Char('q'), KeyCode::Esc],
+        );
+        assert!(!app.should_quit);
+        assert_eq!(app.filter.search_query(), Some("ap"));
+    }
+}
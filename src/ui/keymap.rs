@@ -0,0 +1,352 @@
+// Keymap module - configurable key bindings for normal mode
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+
+/// Normal-mode actions that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Quit,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    NextMatch,
+    PrevMatch,
+    Toggle,
+    SelectAll,
+    SelectNone,
+    SelectNoneVisible,
+    InvertSelection,
+    OpenFolder,
+    CopyPath,
+    Delete,
+    DeleteHighlighted,
+    TogglePermanent,
+    Help,
+    Details,
+    Search,
+    FilterBar,
+    Sort,
+    MinSize,
+    TypeFilter,
+    Fuzzy,
+    ClearFilters,
+    Rescan,
+}
+
+impl KeyAction {
+    /// Every action with its config name and default keys
+    const ALL: &'static [(KeyAction, &'static str, &'static str)] = &[
+        (KeyAction::Quit, "quit", "q esc"),
+        (KeyAction::Up, "up", "up k"),
+        (KeyAction::Down, "down", "down j"),
+        (KeyAction::PageUp, "page_up", "pageup"),
+        (KeyAction::PageDown, "page_down", "pagedown"),
+        (KeyAction::Top, "top", "home g"),
+        (KeyAction::Bottom, "bottom", "end G"),
+        (KeyAction::NextMatch, "next_match", "]"),
+        (KeyAction::PrevMatch, "prev_match", "["),
+        (KeyAction::Toggle, "toggle", "space"),
+        (KeyAction::SelectAll, "select_all", "a ctrl+a"),
+        (KeyAction::SelectNone, "select_none", "n ctrl+d"),
+        (KeyAction::SelectNoneVisible, "select_none_visible", "u"),
+        (KeyAction::InvertSelection, "invert_selection", "i *"),
+        (KeyAction::OpenFolder, "open_folder", "o"),
+        (KeyAction::CopyPath, "copy_path", "y"),
+        (KeyAction::Delete, "delete", "d"),
+        (KeyAction::DeleteHighlighted, "delete_highlighted", "D"),
+        (KeyAction::TogglePermanent, "toggle_permanent", "P"),
+        (KeyAction::Help, "help", "?"),
+        (KeyAction::Details, "details", "enter"),
+        (KeyAction::Search, "search", "/"),
+        (KeyAction::FilterBar, "filter_bar", "F"),
+        (KeyAction::Sort, "sort", "s"),
+        (KeyAction::MinSize, "min_size", "m"),
+        (KeyAction::TypeFilter, "type_filter", "t"),
+        (KeyAction::Fuzzy, "fuzzy", "z"),
+        (KeyAction::ClearFilters, "clear_filters", "c"),
+        (KeyAction::Rescan, "rescan", "r"),
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .map(|(action, _, _)| *action)
+    }
+
+    pub fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(a, _, _)| *a == self)
+            .map(|(_, name, _)| *name)
+            .unwrap_or_default()
+    }
+}
+
+/// A key press as matched by the keymap (Shift is implied by the character)
+type Key = (KeyCode, KeyModifiers);
+
+/// Lookup from key presses to normal-mode actions
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: HashMap<Key, KeyAction>,
+    /// Keys as written in the config (or defaults), per action, for hints
+    labels: Vec<(KeyAction, String)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new()).0
+    }
+}
+
+impl KeyMap {
+    /// Build from `[keybinds]` (action name -> space-separated keys).
+    /// Actions not mentioned keep their defaults; returns warnings for
+    /// unknown actions, unparseable keys and keys bound to two actions
+    pub fn from_config(config: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut custom: Vec<(KeyAction, Key, &str)> = Vec::new();
+
+        for (name, keys) in config {
+            let Some(action) = KeyAction::from_name(name) else {
+                warnings.push(format!("Unknown keybinding action '{}'", name));
+                continue;
+            };
+            for key_str in keys.split_whitespace() {
+                match parse_key(key_str) {
+                    Some(key) => custom.push((action, key, key_str)),
+                    None => warnings.push(format!("Invalid key '{}' for '{}'", key_str, name)),
+                }
+            }
+        }
+
+        let mut bindings: HashMap<Key, KeyAction> = HashMap::new();
+        let mut labels = Vec::new();
+        for &(action, key, key_str) in &custom {
+            match bindings.insert(key, action) {
+                None => labels.push((action, key_str.to_string())),
+                Some(previous) if previous != action => {
+                    // First binding (alphabetical by action) wins
+                    bindings.insert(key, previous);
+                    warnings.push(format!(
+                        "Key '{}' is bound to both '{}' and '{}'; using '{}'",
+                        key_str,
+                        previous.name(),
+                        action.name(),
+                        previous.name()
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        // Defaults for actions the config doesn't rebind
+        for &(action, name, defaults) in KeyAction::ALL {
+            if config.contains_key(name) {
+                continue;
+            }
+            for key_str in defaults.split_whitespace() {
+                let key = parse_key(key_str).expect("default keys are valid");
+                if let Some(&existing) = bindings.get(&key) {
+                    warnings.push(format!(
+                        "Key '{}' is bound to '{}', replacing its default '{}'",
+                        key_str,
+                        existing.name(),
+                        name
+                    ));
+                } else {
+                    bindings.insert(key, action);
+                    labels.push((action, key_str.to_string()));
+                }
+            }
+        }
+
+        (Self { bindings, labels }, warnings)
+    }
+
+    /// Action bound to a key press, if any
+    pub fn action(&self, code: KeyCode, modifiers: KeyModifiers) -> Option<KeyAction> {
+        self.bindings.get(&normalize(code, modifiers)).copied()
+    }
+
+    /// Every key bound to an action, formatted for display
+    pub fn key_labels(&self, action: KeyAction) -> Vec<String> {
+        self.labels
+            .iter()
+            .filter(|(a, _)| *a == action)
+            .map(|(_, key)| key_label(key))
+            .collect()
+    }
+
+    /// First key bound to an action, formatted for display (e.g. "Space")
+    pub fn key_hint(&self, action: KeyAction) -> Option<String> {
+        self.key_labels(action).into_iter().next()
+    }
+}
+
+/// Capitalize named keys and modifiers (`ctrl+space` -> `Ctrl+Space`);
+/// arrows and paging keys get their usual short forms
+fn key_label(key: &str) -> String {
+    key.split('+')
+        .map(|part| {
+            let mut chars = part.chars();
+            match part.to_ascii_lowercase().as_str() {
+                "up" => "↑".to_string(),
+                "down" => "↓".to_string(),
+                "left" => "←".to_string(),
+                "right" => "→".to_string(),
+                "pageup" | "pgup" => "PgUp".to_string(),
+                "pagedown" | "pgdn" => "PgDn".to_string(),
+                _ => match (chars.next(), chars.clone().next()) {
+                    (Some(first), Some(_)) => first.to_uppercase().chain(chars).collect(),
+                    _ => part.to_string(),
+                },
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Drop modifiers the keymap doesn't distinguish
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Key {
+    (
+        code,
+        modifiers.intersection(KeyModifiers::CONTROL | KeyModifiers::ALT),
+    )
+}
+
+/// Parse a key like `x`, `G`, `space`, `ctrl+d`, `alt+x`, `pagedown` or `f5`
+fn parse_key(s: &str) -> Option<Key> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = s;
+    loop {
+        let lower = rest.to_ascii_lowercase();
+        if let Some(stripped) = lower.strip_prefix("ctrl+") {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = &rest[rest.len() - stripped.len()..];
+        } else if let Some(stripped) = lower.strip_prefix("alt+") {
+            modifiers |= KeyModifiers::ALT;
+            rest = &rest[rest.len() - stripped.len()..];
+        } else {
+            break;
+        }
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        // Terminals report Ctrl+letter in lowercase
+        (Some(c), None) if modifiers.contains(KeyModifiers::CONTROL) => {
+            KeyCode::Char(c.to_ascii_lowercase())
+        }
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_ascii_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            other => KeyCode::F(other.strip_prefix('f')?.parse().ok()?),
+        },
+    };
+
+    Some(normalize(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(a, k)| (a.to_string(), k.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            parse_key("x"),
+            Some((KeyCode::Char('x'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("Ctrl+D"),
+            Some((KeyCode::Char('d'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("space"),
+            Some((KeyCode::Char(' '), KeyModifiers::NONE))
+        );
+        assert_eq!(parse_key("f5"), Some((KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(parse_key("bogus"), None);
+    }
+
+    #[test]
+    fn test_rebind_replaces_defaults() {
+        let (keymap, warnings) = KeyMap::from_config(&config(&[("delete", "x Delete")]));
+        assert!(warnings.is_empty());
+
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            keymap.action(KeyCode::Char('x'), none),
+            Some(KeyAction::Delete)
+        );
+        assert_eq!(
+            keymap.action(KeyCode::Delete, none),
+            Some(KeyAction::Delete)
+        );
+        assert_eq!(keymap.action(KeyCode::Char('d'), none), None);
+        // Untouched actions keep their defaults; Shift is ignored
+        assert_eq!(
+            keymap.action(KeyCode::Char('G'), KeyModifiers::SHIFT),
+            Some(KeyAction::Bottom)
+        );
+    }
+
+    #[test]
+    fn test_key_hint() {
+        let keymap = KeyMap::default();
+        assert_eq!(keymap.key_hint(KeyAction::Toggle).as_deref(), Some("Space"));
+        assert_eq!(keymap.key_hint(KeyAction::Quit).as_deref(), Some("q"));
+        assert_eq!(keymap.key_labels(KeyAction::Up), vec!["↑", "k"]);
+
+        let (keymap, _) = KeyMap::from_config(&config(&[("delete", "ctrl+x"), ("help", "")]));
+        assert_eq!(
+            keymap.key_hint(KeyAction::Delete).as_deref(),
+            Some("Ctrl+x")
+        );
+        assert_eq!(keymap.key_hint(KeyAction::Help), None);
+    }
+
+    #[test]
+    fn test_conflicts_warn() {
+        let (keymap, warnings) = KeyMap::from_config(&config(&[
+            ("delete", "s"),
+            ("help", "s"),
+            ("nope", "x"),
+            ("quit", "ctrl+nope"),
+        ]));
+        assert_eq!(warnings.len(), 4);
+
+        // Explicit bindings beat defaults; the first explicit one wins
+        assert_eq!(
+            keymap.action(KeyCode::Char('s'), KeyModifiers::NONE),
+            Some(KeyAction::Delete)
+        );
+    }
+}
@@ -1,7 +1,11 @@
 // UI module - rendering and input handling
 
 pub mod keybinds;
+pub mod keymap;
 pub mod render;
+pub mod theme;
 
 pub use keybinds::{handle_events, Action};
+pub use keymap::KeyMap;
 pub use render::render;
+pub use theme::{no_color_env, Theme};
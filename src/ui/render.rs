@@ -1,18 +1,27 @@
 // Render module - TUI layout and widgets
 
-use crate::app::{App, AppState, InputMode};
+use super::keymap::KeyAction;
+use super::{KeyMap, Theme};
+use crate::app::{App, AppState, DeletionStatus, InputMode};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{
+        Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
     Frame,
 };
+use std::time::SystemTime;
 
 /// Render the application UI
-pub fn render(frame: &mut Frame, app: &App) {
+pub fn render(frame: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    app.screen_height = frame.area().height;
+
     // Adjust layout based on filter bar visibility
-    let chunks = if app.show_filter_bar || app.input_mode == InputMode::Search {
+    let chunks = if app.show_filter_bar || app.input_mode != InputMode::Normal {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -38,74 +47,135 @@ pub fn render(frame: &mut Frame, app: &App) {
     };
 
     let mut idx = 0;
-    render_header(frame, chunks[idx], app);
+    render_header(frame, chunks[idx], app, &theme);
     idx += 1;
 
-    if app.show_filter_bar || app.input_mode == InputMode::Search {
-        render_filter_bar(frame, chunks[idx], app);
+    if app.show_filter_bar || app.input_mode != InputMode::Normal {
+        render_filter_bar(frame, chunks[idx], app, &theme);
         idx += 1;
     }
 
-    render_status(frame, chunks[idx], app);
+    render_status(frame, chunks[idx], app, &theme);
     idx += 1;
-    render_list(frame, chunks[idx], app);
+    render_list(frame, chunks[idx], app, &theme);
     idx += 1;
     render_summary(frame, chunks[idx], app);
     idx += 1;
-    render_keybinds(frame, chunks[idx]);
+    render_keybinds(frame, chunks[idx], &app.keymap, &theme);
+
+    if app.show_details {
+        render_details_overlay(frame, app, &theme);
+    }
 
     // Overlay help if shown
     if app.show_help {
-        render_help_overlay(frame);
+        render_help_overlay(frame, app, &theme);
     }
 
     // Overlay confirm dialog
     if app.state == AppState::Confirming {
-        render_confirm_dialog(frame, app);
+        render_confirm_dialog(frame, app, &theme);
+    }
+
+    // Overlay deletion progress
+    if app.state == AppState::Deleting {
+        if let Some(status) = &app.deletion {
+            render_progress_dialog(frame, app, status, &theme);
+        }
     }
 }
 
-fn render_header(frame: &mut Frame, area: Rect, app: &App) {
+fn render_header(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let mode_indicator = match app.input_mode {
         InputMode::Normal => Span::raw(""),
         InputMode::Search => Span::styled(
             " [SEARCH] ",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.input)
+                .add_modifier(Modifier::BOLD),
+        ),
+        InputMode::MinSize => Span::styled(
+            " [MIN SIZE] ",
+            Style::default()
+                .fg(theme.input)
                 .add_modifier(Modifier::BOLD),
         ),
     };
 
+    let delete_mode = if app.permanent_delete {
+        Span::styled(
+            " [PERMANENT] ",
+            Style::default()
+                .fg(theme.danger)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if app.quarantine_dir.is_some() {
+        Span::styled(" [QUARANTINE] ", Style::default().fg(theme.safe))
+    } else {
+        Span::styled(" [TRASH] ", Style::default().fg(theme.safe))
+    };
+
     let title = Paragraph::new(vec![Line::from(vec![
         Span::styled(
             "claudekill ",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.header)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(concat!("v", env!("CARGO_PKG_VERSION"))),
+        delete_mode,
         mode_indicator,
         Span::raw("                              "),
-        Span::styled("[?] Help  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("[q] Quit", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            key_hints(
+                &app.keymap,
+                &[(&[KeyAction::Help], "Help"), (&[KeyAction::Quit], "Quit")],
+            ),
+            Style::default().fg(theme.muted),
+        ),
     ])])
     .block(Block::default().borders(Borders::ALL));
 
     frame.render_widget(title, area);
 }
 
-fn render_filter_bar(frame: &mut Frame, area: Rect, app: &App) {
+fn render_filter_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let search_text = if app.input_mode == InputMode::Search {
         format!("Search: {}▌", app.search_input)
     } else {
         app.filter
-            .search_query
-            .as_ref()
+            .search_query()
             .map(|s| format!("Search: {}", s))
             .unwrap_or_else(|| "Search: -".to_string())
     };
+    let search_text = if app.filter.invalid_regex() {
+        format!("{} (invalid regex)", search_text)
+    } else if app.filter.fuzzy {
+        format!("{} (fuzzy)", search_text)
+    } else {
+        search_text
+    };
+
+    let min_size_text = if app.input_mode == InputMode::MinSize {
+        format!("Min size: {}▌", app.size_input)
+    } else {
+        app.filter
+            .min_size
+            .map(|s| format!("Min size: {}", crate::utils::format_size(s)))
+            .unwrap_or_else(|| "Min size: -".to_string())
+    };
 
-    let sort_text = format!("Sort: {}", app.sort_order.label());
+    let type_text = match app.filter.project_types.as_slice() {
+        [] => "Type: all".to_string(),
+        types => format!("Type: {}", types.join(", ")),
+    };
+
+    let sort_label = if app.show_date_column {
+        app.sort_order.date_label()
+    } else {
+        app.sort_order.label()
+    };
+    let sort_text = format!("Sort: {}", sort_label);
 
     let filter_status = if app.filter.is_active() {
         format!("Showing {} of {}", app.visible_count(), app.folders.len())
@@ -113,24 +183,43 @@ fn render_filter_bar(frame: &mut Frame, area: Rect, app: &App) {
         String::new()
     };
 
-    let filter_text = format!(" {}  │  {}  {}", search_text, sort_text, filter_status);
+    let filter_text = format!(
+        " {}  │  {}  │  {}  │  {}  {}",
+        search_text, min_size_text, type_text, sort_text, filter_status
+    );
 
-    let style = if app.input_mode == InputMode::Search {
-        Style::default().fg(Color::Yellow)
+    let style = if app.input_mode != InputMode::Normal {
+        Style::default().fg(theme.input)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme.muted)
     };
 
+    let hints = key_hints(
+        &app.keymap,
+        &[
+            (&[KeyAction::Search], "Search"),
+            (&[KeyAction::MinSize], "Min size"),
+            (&[KeyAction::TypeFilter], "Type"),
+            (&[KeyAction::Sort], "Sort"),
+            (&[KeyAction::ClearFilters], "Clear"),
+        ],
+    );
     let bar = Paragraph::new(filter_text).style(style).block(
         Block::default()
             .borders(Borders::ALL)
-            .title(" Filters [/] Search  [s] Sort  [c] Clear "),
+            .title(format!(" Filters {} ", hints)),
     );
 
     frame.render_widget(bar, area);
 }
 
-fn render_status(frame: &mut Frame, area: Rect, app: &App) {
+fn render_status(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let skipped = if app.scan_errors.is_empty() {
+        String::new()
+    } else {
+        format!("  ({} paths skipped (unreadable))", app.scan_errors.len())
+    };
+
     let status_text = match app.state {
         AppState::Scanning => {
             let path = app
@@ -138,15 +227,19 @@ fn render_status(frame: &mut Frame, area: Rect, app: &App) {
                 .as_ref()
                 .map(|p| p.display().to_string())
                 .unwrap_or_default();
-            let truncated = if path.len() > 40 {
-                format!("...{}", &path[path.len() - 37..])
-            } else {
-                path
-            };
+            let truncated = crate::utils::truncate_start(&path, 40);
+            let estimate = app
+                .last_scan_duration
+                .map(|d| format!("  (Last scan took ~{}s)", d.as_secs().max(1)))
+                .unwrap_or_default();
             format!(
-                "Scanning: {:40}           Found: {}",
+                "{} Scanning: {:40}  Found: {} folders, {} total{}{}",
+                app.spinner(),
                 truncated,
-                app.folders.len()
+                app.folders.len(),
+                crate::utils::format_size(app.total_size()),
+                estimate,
+                skipped
             )
         }
         AppState::Browsing => {
@@ -154,8 +247,9 @@ fn render_status(frame: &mut Frame, area: Rect, app: &App) {
                 msg.clone()
             } else {
                 format!(
-                    "Scan complete                                     Found: {}",
-                    app.folders.len()
+                    "Scan complete                                     Found: {}{}",
+                    app.folders.len(),
+                    skipped
                 )
             }
         }
@@ -165,11 +259,11 @@ fn render_status(frame: &mut Frame, area: Rect, app: &App) {
     };
 
     let color = match app.state {
-        AppState::Scanning => Color::Yellow,
-        AppState::Browsing => Color::Green,
-        AppState::Confirming => Color::Magenta,
-        AppState::Deleting => Color::Red,
-        AppState::Done => Color::Green,
+        AppState::Scanning => theme.status_scanning,
+        AppState::Browsing => theme.status_browsing,
+        AppState::Confirming => theme.status_confirming,
+        AppState::Deleting => theme.status_deleting,
+        AppState::Done => theme.status_browsing,
     };
 
     let status = Paragraph::new(status_text)
@@ -179,7 +273,7 @@ fn render_status(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(status, area);
 }
 
-fn render_list(frame: &mut Frame, area: Rect, app: &App) {
+fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
     let home = dirs::home_dir();
     let visible_indices = app.visible_folder_indices();
 
@@ -196,42 +290,87 @@ fn render_list(frame: &mut Frame, area: Rect, app: &App) {
                 .unwrap_or(false);
 
             let selected_marker = if folder.selected { "●" } else { " " };
-            let size = format!("{:>10}", folder.size_display());
+            let size = if app.show_disk_size {
+                let disk = folder
+                    .other_size()
+                    .map(crate::utils::format_size)
+                    .unwrap_or_default();
+                format!("{:>10} {:>10}", folder.size_display(), disk)
+            } else {
+                format!("{:>10}", folder.size_display())
+            };
+
+            // Either the absolute date or the relative age, to keep rows narrow
+            let age = match folder.modified_at {
+                Some(modified) if app.show_date_column => {
+                    chrono::DateTime::<chrono::Local>::from(modified)
+                        .format("%Y-%m-%d")
+                        .to_string()
+                }
+                Some(modified) => {
+                    let age = SystemTime::now()
+                        .duration_since(modified)
+                        .unwrap_or_default();
+                    crate::utils::format_age(age)
+                }
+                None => "?".to_string(),
+            };
 
             // Truncate path to fit
             let path = folder.path.display().to_string();
             let max_path_len = if is_global { 38 } else { 45 };
-            let display_path = if path.len() > max_path_len {
-                format!("...{}", &path[path.len() - max_path_len + 3..])
-            } else {
-                path
-            };
+            let display_path = crate::utils::truncate_start(&path, max_path_len);
 
-            // Add warning for global folder
-            let project_type = if is_global {
+            // Add warning for global folder, lock for protected ones,
+            // clock for ones that look in use
+            let project_type = if app.is_protected(folder) {
+                format!("{} 🔒", folder.project_type)
+            } else if is_global {
                 format!("{} ⚠GLOBAL", folder.project_type)
+            } else if app.is_recent(folder) {
+                format!("{} ⏱", folder.project_type)
             } else {
                 folder.project_type.clone()
             };
 
             let style = if display_idx == app.selected_index {
                 Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD)
+                    .bg(theme.cursor)
+                    .add_modifier(theme.cursor_modifier)
             } else if is_global {
-                Style::default().fg(Color::Red)
+                Style::default().fg(theme.global_warning)
             } else if folder.selected {
-                Style::default().fg(Color::Cyan)
+                Style::default().fg(theme.selected)
+            } else if app.is_new_arrival(folder) {
+                Style::default()
+                    .fg(theme.arrival)
+                    .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
             };
 
-            let content = format!(
-                "{} {} {:48} {:10}",
-                selected_marker, size, display_path, project_type
+            // Only the size is colored by magnitude; the row style covers the rest
+            let size_style = match app.size_colors {
+                Some((_, large)) if folder.size >= large => Style::default().fg(theme.size_large),
+                Some((medium, _)) if folder.size >= medium => {
+                    Style::default().fg(theme.size_medium)
+                }
+                Some(_) => Style::default().fg(theme.size_small),
+                None => Style::default(),
+            };
+
+            let age_width = if app.show_date_column { 10 } else { 4 };
+            let rest = format!(
+                " {:>age_width$} {:48} {:10}",
+                age, display_path, project_type
             );
 
-            ListItem::new(content).style(style)
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ", selected_marker)),
+                Span::styled(size, size_style),
+                Span::raw(rest),
+            ]))
+            .style(style)
         })
         .collect();
 
@@ -247,7 +386,26 @@ fn render_list(frame: &mut Frame, area: Rect, app: &App) {
 
     let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
 
-    frame.render_widget(list, area);
+    // Remembered so mouse clicks can be mapped back to rows
+    app.list_area = area;
+    let selected = (!visible_indices.is_empty()).then_some(app.selected_index);
+    app.list_state.select(selected);
+    frame.render_stateful_widget(list, area, &mut app.list_state);
+
+    // Scrollbar only when the list overflows the viewport
+    let viewport = area.height.saturating_sub(2) as usize;
+    if visible_indices.len() > viewport {
+        let mut scrollbar_state =
+            ScrollbarState::new(visible_indices.len()).position(app.selected_index);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
 }
 
 fn render_summary(frame: &mut Frame, area: Rect, app: &App) {
@@ -264,121 +422,445 @@ fn render_summary(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(summary, area);
 }
 
-fn render_keybinds(frame: &mut Frame, area: Rect) {
-    let keybinds = Paragraph::new(
-        "[Space] Toggle  [a/n] All/None  [d] Delete  [/] Search  [s] Sort  [?] Help  [q] Quit",
-    )
-    .style(Style::default().fg(Color::DarkGray));
+/// "[key] Label" hints using the configured keys; unbound actions are left out
+fn key_hints(keymap: &KeyMap, hints: &[(&[KeyAction], &str)]) -> String {
+    hints
+        .iter()
+        .filter_map(|(actions, label)| {
+            let keys: Option<Vec<String>> = actions.iter().map(|&a| keymap.key_hint(a)).collect();
+            keys.map(|keys| format!("[{}] {}", keys.join("/"), label))
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+fn render_keybinds(frame: &mut Frame, area: Rect, keymap: &KeyMap, theme: &Theme) {
+    let text = key_hints(
+        keymap,
+        &[
+            (&[KeyAction::Toggle], "Toggle"),
+            (&[KeyAction::SelectAll, KeyAction::SelectNone], "All/None"),
+            (&[KeyAction::Delete], "Delete"),
+            (&[KeyAction::Search], "Search"),
+            (&[KeyAction::Sort], "Sort"),
+            (&[KeyAction::Help], "Help"),
+            (&[KeyAction::Quit], "Quit"),
+        ],
+    );
+    let keybinds = Paragraph::new(text).style(Style::default().fg(theme.muted));
 
     frame.render_widget(keybinds, area);
 }
 
-fn render_help_overlay(frame: &mut Frame) {
-    let area = centered_rect(60, 70, frame.area());
-
-    let help_text = vec![
-        "",
-        "  Navigation",
-        "  ──────────",
-        "  ↑/k, ↓/j   Move up/down",
-        "  PgUp/PgDn  Page up/down",
-        "  g/G        Go to top/bottom",
-        "",
-        "  Selection",
-        "  ─────────",
-        "  Space      Toggle selection",
-        "  a/n        Select all/none",
-        "  d          Delete selected",
-        "",
-        "  Search & Filter",
-        "  ───────────────",
-        "  /          Enter search mode",
-        "  F          Toggle filter bar",
-        "  s          Cycle sort order",
-        "  c          Clear all filters",
-        "",
-        "  Other",
-        "  ─────",
-        "  ?          Toggle this help",
-        "  q/Esc      Quit",
-        "",
-        "  Press any key to close",
-        "",
-    ];
+/// A row of the help overlay
+enum HelpRow {
+    Heading(&'static str),
+    /// Actions whose configured keys fill the key column
+    Keys(&'static [KeyAction], &'static str),
+    /// Keys that can't be rebound
+    Fixed(&'static str, &'static str),
+}
 
-    let help = Paragraph::new(help_text.join("\n")).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(" Help ")
-            .style(Style::default().bg(Color::Black)),
-    );
+const HELP_ROWS: &[HelpRow] = &[
+    HelpRow::Heading("Navigation"),
+    HelpRow::Keys(&[KeyAction::Up, KeyAction::Down], "Move up/down"),
+    HelpRow::Keys(&[KeyAction::PageUp, KeyAction::PageDown], "Page up/down"),
+    HelpRow::Keys(&[KeyAction::Top, KeyAction::Bottom], "Go to top/bottom"),
+    HelpRow::Fixed("5j, 10k", "Move by a count; 5G goes to row 5"),
+    HelpRow::Keys(
+        &[KeyAction::NextMatch, KeyAction::PrevMatch],
+        "Next/previous folder of the same type",
+    ),
+    HelpRow::Fixed("", "(among folders the search and filters show)"),
+    HelpRow::Heading("Selection"),
+    HelpRow::Keys(&[KeyAction::Toggle], "Toggle selection"),
+    HelpRow::Keys(&[KeyAction::SelectAll], "Select all visible"),
+    HelpRow::Keys(&[KeyAction::SelectNone], "Select none (including hidden)"),
+    HelpRow::Keys(&[KeyAction::SelectNoneVisible], "Deselect visible"),
+    HelpRow::Keys(&[KeyAction::InvertSelection], "Invert visible selection"),
+    HelpRow::Keys(&[KeyAction::OpenFolder], "Open project in file manager"),
+    HelpRow::Keys(&[KeyAction::CopyPath], "Copy path to clipboard"),
+    HelpRow::Keys(&[KeyAction::Delete], "Delete selected"),
+    HelpRow::Keys(
+        &[KeyAction::DeleteHighlighted],
+        "Delete highlighted folder only",
+    ),
+    HelpRow::Keys(
+        &[KeyAction::TogglePermanent],
+        "Toggle Trash/permanent delete",
+    ),
+    HelpRow::Heading("Search & Filter"),
+    HelpRow::Keys(
+        &[KeyAction::Search],
+        "Enter search mode (start with / for regex)",
+    ),
+    HelpRow::Keys(&[KeyAction::MinSize], "Set minimum size (e.g. 50MB)"),
+    HelpRow::Keys(&[KeyAction::TypeFilter], "Cycle project type filter"),
+    HelpRow::Keys(&[KeyAction::Fuzzy], "Toggle fuzzy search"),
+    HelpRow::Keys(&[KeyAction::FilterBar], "Toggle filter bar"),
+    HelpRow::Keys(&[KeyAction::Sort], "Cycle sort order"),
+    HelpRow::Keys(&[KeyAction::ClearFilters], "Clear all filters"),
+    HelpRow::Keys(&[KeyAction::Rescan], "Re-scan"),
+    HelpRow::Heading("Other"),
+    HelpRow::Keys(&[KeyAction::Help], "Toggle this help (↑/↓ to scroll)"),
+    HelpRow::Keys(
+        &[KeyAction::Details],
+        "Show details of the highlighted folder",
+    ),
+    HelpRow::Fixed("Esc", "Cancel scan (while scanning)"),
+    HelpRow::Keys(&[KeyAction::Quit], "Quit"),
+];
+
+/// Lines of the help overlay, with the keys from `keymap` (rows for
+/// unbound actions are left out)
+fn help_lines(keymap: &KeyMap) -> Vec<String> {
+    let mut lines = Vec::new();
+    for row in HELP_ROWS {
+        let (keys, description) = match row {
+            HelpRow::Heading(title) => {
+                lines.push(String::new());
+                lines.push(format!("  {}", title));
+                lines.push(format!("  {}", "─".repeat(title.chars().count())));
+                continue;
+            }
+            HelpRow::Keys(actions, description) => {
+                let keys: Vec<String> = actions
+                    .iter()
+                    .map(|&a| keymap.key_labels(a).join("/"))
+                    .filter(|k| !k.is_empty())
+                    .collect();
+                if keys.is_empty() {
+                    continue;
+                }
+                (keys.join(", "), *description)
+            }
+            HelpRow::Fixed(keys, description) => (keys.to_string(), *description),
+        };
+        lines.push(format!("  {:<10} {}", keys, description));
+    }
+    lines.push(String::new());
+    lines
+}
+
+/// Furthest the help overlay can scroll on a screen this tall
+pub fn help_max_scroll(keymap: &KeyMap, screen_height: u16) -> u16 {
+    let len = help_lines(keymap).len() as u16;
+    let height = (len + 2).min(screen_height);
+    len.saturating_sub(height.saturating_sub(2))
+}
+
+fn render_help_overlay(frame: &mut Frame, app: &App, theme: &Theme) {
+    // As tall as the content, but never taller than the terminal
+    let screen = frame.area();
+    let width = screen.width * 60 / 100;
+    let lines = help_lines(&app.keymap);
+    let height = (lines.len() as u16 + 2).min(screen.height);
+    let area = Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    };
+
+    let help = Paragraph::new(lines.join("\n"))
+        .scroll((app.help_scroll, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Help ")
+                .title_bottom(" ↑/↓ scroll · ?/Esc close ")
+                .style(Style::default().bg(theme.dialog_bg)),
+        );
 
     frame.render_widget(Clear, area);
     frame.render_widget(help, area);
 }
 
-fn render_confirm_dialog(frame: &mut Frame, app: &App) {
-    let area = centered_rect(60, 50, frame.area());
+/// Everything known about the highlighted folder, with the full path
+fn render_details_overlay(frame: &mut Frame, app: &App, theme: &Theme) {
+    let Some(folder) = app.highlighted_folder() else {
+        return;
+    };
+    let bytes = |n: u64| format!("{} ({} bytes)", crate::utils::format_size(n), n);
+
+    let mut lines = vec![
+        format!("Path:       {}", folder.path.display()),
+        format!("Type:       {}", folder.project_type),
+        format!("Size:       {}", bytes(folder.size)),
+    ];
+    if folder.disk_size != folder.size {
+        lines.push(format!("On disk:    {}", bytes(folder.disk_size)));
+    }
+    if folder.apparent_size != folder.size {
+        lines.push(format!("Apparent:   {}", bytes(folder.apparent_size)));
+    }
+    lines.push(format!("Files:      {}", folder.file_count));
+    if let Some((path, size)) = &folder.largest_file {
+        let name = path.strip_prefix(&folder.path).unwrap_or(path);
+        lines.push(format!(
+            "Largest:    {} ({})",
+            name.display(),
+            crate::utils::format_size(*size)
+        ));
+    }
+    lines.push(match folder.modified_at {
+        Some(modified) => {
+            let age = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+            format!(
+                "Modified:   {} ({} ago)",
+                chrono::DateTime::<chrono::Local>::from(modified).format("%Y-%m-%d %H:%M:%S"),
+                crate::utils::format_age(age)
+            )
+        }
+        None => "Modified:   unknown".to_string(),
+    });
+    if let Some(root) = &folder.root {
+        lines.push(format!("Scan root:  {}", root.display()));
+    }
+    if app.is_protected(folder) {
+        lines.push("Protected:  yes, can't be deleted".to_string());
+    } else if app.is_recent(folder) {
+        lines.push("In use:     modified recently".to_string());
+    }
+
+    let area = centered_rect(70, 50, frame.area());
+    let details = Paragraph::new(lines.join("\n"))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Details ")
+                .title_bottom(" ↑/↓ next folder · Enter/Esc close ")
+                .style(Style::default().bg(theme.dialog_bg)),
+        );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(details, area);
+}
+
+fn render_confirm_dialog(frame: &mut Frame, app: &App, theme: &Theme) {
+    let height = if app.confirm_preview.is_some() {
+        80
+    } else {
+        50
+    };
+    let area = centered_rect(60, height, frame.area());
 
-    let count = app.selected_count();
-    let size = crate::utils::format_size(app.selected_size());
+    let targets = app.deletion_targets();
+    let count = targets.len();
+    let size = crate::utils::format_size(app.target_size());
 
-    let (method, warning) = if app.permanent_delete {
-        ("PERMANENTLY DELETE", "⚠ This cannot be undone!")
+    let (method, warning) = if let (true, Some(dir)) = (app.permanent_delete, &app.backup_dir) {
+        (
+            "PERMANENTLY DELETE",
+            format!("Backed up to {}. Restore with --undo.", dir.display()),
+        )
+    } else if app.permanent_delete {
+        ("PERMANENTLY DELETE", "⚠ This cannot be undone!".to_string())
+    } else if let Some(dir) = &app.quarantine_dir {
+        (
+            "Quarantine",
+            format!("Moved to {}. Restore with --undo.", dir.display()),
+        )
     } else {
-        ("Move to Trash", "You can restore from Trash later.")
+        (
+            "Move to Trash",
+            "You can restore from Trash later.".to_string(),
+        )
     };
 
     // Build folder list preview (show first 5)
-    let selected_folders: Vec<String> = app
-        .get_selected_folders()
+    let selected_folders: Vec<String> = targets
         .iter()
         .take(5)
         .map(|f| {
             let path = f.path.display().to_string();
-            if path.len() > 50 {
-                format!("  • ...{}", &path[path.len() - 47..])
-            } else {
-                format!("  • {}", path)
-            }
+            format!("  • {}", crate::utils::truncate_start(&path, 50))
         })
         .collect();
 
     let mut text = vec![
         String::new(),
         format!("  {} {} folder(s) ({})", method, count, size),
-        String::new(),
     ];
 
+    // What kinds of projects lose their config, e.g. after filter + select-all
+    let by_type = app.targets_by_type();
+    for (project_type, type_count, type_size) in by_type.iter().take(6) {
+        text.push(format!(
+            "    {}: {} ({})",
+            project_type,
+            type_count,
+            crate::utils::format_size(*type_size)
+        ));
+    }
+    if by_type.len() > 6 {
+        text.push(format!("    ... and {} more types", by_type.len() - 6));
+    }
+    text.push(String::new());
+
     text.extend(selected_folders);
 
     if count > 5 {
         text.push(format!("  ... and {} more", count - 5));
     }
 
+    if let Some((path, children)) = &app.confirm_preview {
+        let name = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        text.push(String::new());
+        match children {
+            Some(children) => text.push(format!(
+                "  Contents of {}/.claude ({} entries):",
+                name,
+                children.len()
+            )),
+            None => text.push(format!("  Measuring contents of {}/.claude...", name)),
+        }
+        let children = children.as_deref().unwrap_or_default();
+        for child in children.iter().take(8) {
+            let suffix = if child.is_dir { "/" } else { "" };
+            text.push(format!(
+                "    {:>10}  {}{}",
+                crate::utils::format_size(child.size),
+                child.name,
+                suffix
+            ));
+        }
+        if children.len() > 8 {
+            text.push(format!("    ... and {} more", children.len() - 8));
+        }
+        if let Some(largest) = children.iter().find(|c| !c.is_dir) {
+            text.push(format!(
+                "  Largest file: {} ({})",
+                largest.name,
+                crate::utils::format_size(largest.size)
+            ));
+        }
+    }
+
     text.push(String::new());
     text.push(format!("  {}", warning));
+    let nested = app.nested_in_targets();
+    if nested > 0 {
+        text.push(format!(
+            "  ⚠ This will also delete {} nested .claude folder(s)",
+            nested
+        ));
+    }
+    let hidden = app.hidden_target_count();
+    if hidden > 0 {
+        text.push(format!(
+            "  {} selected folder(s) are hidden by filters and will be deleted too",
+            hidden
+        ));
+    }
+    if let Some(folder) = app.targeted_current_project() {
+        text.push(format!(
+            "  ⚠ This is your current project: {}",
+            folder.path.display()
+        ));
+    }
+    let recent = app.recent_target_count();
+    if recent > 0 {
+        text.push(format!(
+            "  ⏱ {} folder(s) modified recently - may be in use!",
+            recent
+        ));
+    }
+    let grown = app.grown_since_scan.as_deref().unwrap_or_default();
+    for path in grown.iter().take(3) {
+        text.push(format!(
+            "  ⚠ {} has grown since the scan - still in use?",
+            path.display()
+        ));
+    }
+    if grown.len() > 3 {
+        text.push(format!("    ... and {} more", grown.len() - 3));
+    }
     text.push(String::new());
-    text.push("  Confirm? [y/N]".to_string());
+    let preview_hint = if app.confirm_preview.is_some() {
+        "hide contents"
+    } else {
+        "preview contents"
+    };
+    if let Some(remaining) = app.confirm_countdown() {
+        text.push(format!(
+            "  Confirm in {}s...   (p: {})",
+            remaining.as_secs_f64().ceil(),
+            preview_hint
+        ));
+    } else if app.recent_confirmed {
+        text.push("  Press y again to delete recently modified folders".to_string());
+    } else {
+        text.push(format!("  Confirm? [y/N]   (p: {})", preview_hint));
+    }
     text.push(String::new());
 
     let color = if app.permanent_delete {
-        Color::Red
+        theme.danger
     } else {
-        Color::Yellow
+        theme.dialog
     };
 
     let dialog = Paragraph::new(text.join("\n")).block(
         Block::default()
             .borders(Borders::ALL)
             .title(" Confirm Deletion ")
-            .style(Style::default().bg(Color::Black).fg(color)),
+            .style(Style::default().bg(theme.dialog_bg).fg(color)),
     );
 
     frame.render_widget(Clear, area);
     frame.render_widget(dialog, area);
 }
 
+fn render_progress_dialog(frame: &mut Frame, app: &App, status: &DeletionStatus, theme: &Theme) {
+    let full = centered_rect(60, 50, frame.area());
+    let area = Rect {
+        y: full.y + full.height.saturating_sub(5) / 2,
+        height: full.height.min(5),
+        ..full
+    };
+
+    let (title, color) = if app.permanent_delete {
+        (" Deleting ", theme.danger)
+    } else if app.quarantine_dir.is_some() {
+        (" Quarantining ", theme.safe)
+    } else {
+        (" Moving to Trash ", theme.safe)
+    };
+
+    // File and byte counts only come from permanent deletes
+    let mut label = format!("{}/{} folders", status.done.folders, status.total_folders);
+    if status.done.files > 0 {
+        label.push_str(&format!(
+            "  {} files, {} of {}",
+            status.done.files,
+            crate::utils::format_size(status.done.bytes),
+            crate::utils::format_size(status.total_bytes)
+        ));
+    }
+
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .style(Style::default().bg(theme.dialog_bg).fg(color)),
+        )
+        .gauge_style(Style::default().fg(color).bg(theme.dialog_bg))
+        .ratio(status.ratio())
+        .label(label);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(gauge, area);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -398,3 +880,213 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::ClaudeFolder;
+    use ratatui::{backend::TestBackend, style::Color, Terminal};
+    use std::path::PathBuf;
+
+    fn app_with(folders: &[(PathBuf, u64)]) -> App {
+        let mut app = App::new(false);
+        for (path, size) in folders {
+            app.add_folder(ClaudeFolder {
+                path: path.clone(),
+                size: *size,
+                project_type: "Rust".to_string(),
+                ..Default::default()
+            });
+        }
+        app.complete_scan();
+        app
+    }
+
+    /// Render a frame and return the screen, one string per row
+    fn draw(app: &mut App) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| render(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn screen_contains(screen: &[String], text: &str) -> bool {
+        screen.iter().any(|row| row.contains(text))
+    }
+
+    #[test]
+    fn test_render_main_screen() {
+        let global = dirs::home_dir().unwrap_or_default().join(".claude");
+        let mut app = app_with(&[(PathBuf::from("/p/web/.claude"), 2048), (global, 1024)]);
+        let screen = draw(&mut app);
+
+        assert!(screen[1].contains("claudekill"));
+        assert!(screen[1].contains("[TRASH]"));
+        assert!(screen_contains(&screen, "/p/web/.claude"));
+        assert!(screen_contains(&screen, "2.0 KB"));
+        assert!(screen_contains(&screen, "⚠GLOBAL"));
+        assert!(screen_contains(&screen, "Total: 3.0 KB"));
+        // Key hints sit on the first of the two bottom rows
+        assert!(screen[28].contains("[Space] Toggle"));
+    }
+
+    #[test]
+    fn test_render_key_hints_follow_keymap() {
+        let mut app = app_with(&[(PathBuf::from("/p/web/.claude"), 2048)]);
+        let config = [("delete".to_string(), "x".to_string())]
+            .into_iter()
+            .collect();
+        app.keymap = KeyMap::from_config(&config).0;
+        let screen = draw(&mut app);
+
+        assert!(screen[28].contains("[x] Delete"));
+        assert!(screen[28].contains("[a/n] All/None"));
+        assert!(screen[1].contains("[?] Help  [q] Quit"));
+
+        let config = [("min_size".to_string(), "M".to_string())]
+            .into_iter()
+            .collect();
+        app.keymap = KeyMap::from_config(&config).0;
+        app.show_filter_bar = true;
+        assert!(screen_contains(&draw(&mut app), "[M] Min size"));
+    }
+
+    #[test]
+    fn test_help_lines_follow_keymap() {
+        let lines = help_lines(&KeyMap::default());
+        assert!(lines.contains(&"  ↑/k, ↓/j   Move up/down".to_string()));
+        assert!(lines.contains(&"  d          Delete selected".to_string()));
+
+        let config = [("delete".to_string(), "x".to_string())]
+            .into_iter()
+            .collect();
+        let lines = help_lines(&KeyMap::from_config(&config).0);
+        assert!(lines.contains(&"  x          Delete selected".to_string()));
+        assert!(!lines.iter().any(|l| l.starts_with("  d ")));
+    }
+
+    #[test]
+    fn test_render_filter_bar_keeps_layout() {
+        let mut app = app_with(&[(PathBuf::from("/p/web/.claude"), 2048)]);
+        app.show_filter_bar = true;
+        app.filter.set_search(Some("web".to_string()));
+        let screen = draw(&mut app);
+
+        // Every section still lands in its own chunk with the bar shown
+        assert!(screen[1].contains("claudekill"));
+        assert!(screen[4].contains("web"));
+        assert!(screen_contains(&screen, ".claude folders (1 of 1)"));
+        assert!(screen_contains(&screen, "/p/web/.claude"));
+        assert!(screen_contains(&screen, "Total: 2.0 KB"));
+        assert!(screen[28].contains("[Space] Toggle"));
+    }
+
+    #[test]
+    fn test_render_details_shows_full_path() {
+        let path = PathBuf::from("/home/someone/projects/clients/acme/web-frontend/.claude");
+        let mut app = app_with(&[(path.clone(), 123_456)]);
+
+        // The list row truncates the path; the detail pane doesn't
+        let screen = draw(&mut app);
+        assert!(!screen_contains(&screen, "/home/someone/projects"));
+
+        app.show_details = true;
+        let screen = draw(&mut app);
+        assert!(screen_contains(&screen, &path.display().to_string()));
+        assert!(screen_contains(&screen, "123456 bytes"));
+    }
+
+    #[test]
+    fn test_render_size_colors() {
+        let mb = 1024 * 1024;
+        let mut app = app_with(&[
+            (PathBuf::from("/p/big/.claude"), 500 * mb),
+            (PathBuf::from("/p/mid/.claude"), 50 * mb),
+            (PathBuf::from("/p/small/.claude"), 2048),
+        ]);
+        for folder in &mut app.folders {
+            folder.selected = folder.size < mb;
+        }
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| render(frame, &mut app)).unwrap();
+        let buffer = terminal.backend().buffer();
+
+        // Foreground of the first cell of `text` on screen
+        let fg_at = |text: &str| {
+            (0..buffer.area.height)
+                .find_map(|y| {
+                    let row: Vec<&str> = (0..buffer.area.width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect();
+                    let x = row.concat().find(text)?;
+                    let column = row.concat()[..x].chars().count() as u16;
+                    Some(buffer[(column, y)].fg)
+                })
+                .unwrap()
+        };
+        let theme = Theme::default();
+        assert_eq!(fg_at("500.0 MB"), theme.size_large);
+        assert_eq!(fg_at("50.0 MB"), theme.size_medium);
+        assert_eq!(fg_at("2.0 KB"), theme.size_small);
+        // The rest of the row keeps its own style
+        assert_eq!(fg_at("/p/small/.claude"), theme.selected);
+    }
+
+    #[test]
+    fn test_render_plain_theme_has_no_colors() {
+        let mut app = app_with(&[(PathBuf::from("/p/web/.claude"), 2048)]);
+        app.theme = Theme::plain();
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| render(frame, &mut app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert!(buffer
+            .content()
+            .iter()
+            .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+        // The cursor row still stands out
+        assert!(buffer
+            .content()
+            .iter()
+            .any(|cell| cell.modifier.contains(Modifier::REVERSED)));
+    }
+
+    #[test]
+    fn test_render_confirm_dialog() {
+        let mut app = app_with(&[(PathBuf::from("/p/web/.claude"), 2048)]);
+        app.folders[0].selected = true;
+        app.permanent_delete = true;
+        app.state = AppState::Confirming;
+        let screen = draw(&mut app);
+
+        assert!(screen_contains(&screen, "Confirm Deletion"));
+        assert!(screen_contains(
+            &screen,
+            "PERMANENTLY DELETE 1 folder(s) (2.0 KB)"
+        ));
+        assert!(screen_contains(&screen, "Rust: 1 (2.0 KB)"));
+        assert!(screen_contains(&screen, "This cannot be undone"));
+        assert!(!screen_contains(&screen, "current project"));
+
+        app.backup_dir = Some(PathBuf::from("/bak"));
+        let screen = draw(&mut app);
+        assert!(screen_contains(
+            &screen,
+            "Backed up to /bak. Restore with --undo."
+        ));
+        app.backup_dir = None;
+
+        app.current_dir = Some(PathBuf::from("/p/web"));
+        let screen = draw(&mut app);
+        assert!(screen_contains(
+            &screen,
+            "⚠ This is your current project: /p/web/.claude"
+        ));
+    }
+}
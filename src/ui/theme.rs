@@ -0,0 +1,165 @@
+// Theme module - semantic colors for the TUI
+
+use crate::config::ThemeConfig;
+use ratatui::style::{Color, Modifier};
+use std::str::FromStr;
+
+/// Colors used by the render functions, by role
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// App name in the header
+    pub header: Color,
+    /// Background of the highlighted row
+    pub cursor: Color,
+    /// Folders marked for deletion
+    pub selected: Color,
+    /// Large folders that just turned up during a scan
+    pub arrival: Color,
+    /// The global ~/.claude folder
+    pub global_warning: Color,
+    /// Size column of small, medium and large folders
+    pub size_small: Color,
+    pub size_medium: Color,
+    pub size_large: Color,
+    /// Hints and inactive text
+    pub muted: Color,
+    /// Input prompts and mode indicators
+    pub input: Color,
+    /// Permanent delete mode
+    pub danger: Color,
+    /// Trash and quarantine modes
+    pub safe: Color,
+    pub status_scanning: Color,
+    pub status_browsing: Color,
+    pub status_confirming: Color,
+    pub status_deleting: Color,
+    /// Text of the confirm dialog
+    pub dialog: Color,
+    /// Background of overlays (help, confirm dialog)
+    pub dialog_bg: Color,
+    /// How the highlighted row stands out besides its background
+    pub cursor_modifier: Modifier,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            header: Color::Cyan,
+            cursor: Color::DarkGray,
+            selected: Color::Cyan,
+            arrival: Color::LightYellow,
+            global_warning: Color::Red,
+            size_small: Color::Green,
+            size_medium: Color::Yellow,
+            size_large: Color::Red,
+            muted: Color::DarkGray,
+            input: Color::Yellow,
+            danger: Color::Red,
+            safe: Color::Green,
+            status_scanning: Color::Yellow,
+            status_browsing: Color::Green,
+            status_confirming: Color::Magenta,
+            status_deleting: Color::Red,
+            dialog: Color::Yellow,
+            dialog_bg: Color::Black,
+            cursor_modifier: Modifier::BOLD,
+        }
+    }
+}
+
+impl Theme {
+    /// No colors at all, for `--no-color` and `NO_COLOR`; the highlighted
+    /// row is shown in reverse video instead
+    pub fn plain() -> Self {
+        Self {
+            header: Color::Reset,
+            cursor: Color::Reset,
+            selected: Color::Reset,
+            arrival: Color::Reset,
+            global_warning: Color::Reset,
+            size_small: Color::Reset,
+            size_medium: Color::Reset,
+            size_large: Color::Reset,
+            muted: Color::Reset,
+            input: Color::Reset,
+            danger: Color::Reset,
+            safe: Color::Reset,
+            status_scanning: Color::Reset,
+            status_browsing: Color::Reset,
+            status_confirming: Color::Reset,
+            status_deleting: Color::Reset,
+            dialog: Color::Reset,
+            dialog_bg: Color::Reset,
+            cursor_modifier: Modifier::BOLD | Modifier::REVERSED,
+        }
+    }
+}
+
+/// Whether the `NO_COLOR` environment variable asks for plain output
+/// (set and non-empty, see <https://no-color.org>)
+pub fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+impl From<&ThemeConfig> for Theme {
+    /// Unset or unparseable colors keep their default
+    fn from(config: &ThemeConfig) -> Self {
+        let default = Self::default();
+        let pick = |value: &Option<String>, fallback: Color| {
+            value
+                .as_deref()
+                .and_then(|s| Color::from_str(s.trim()).ok())
+                .unwrap_or(fallback)
+        };
+
+        Self {
+            header: pick(&config.header, default.header),
+            cursor: pick(&config.cursor, default.cursor),
+            selected: pick(&config.selected, default.selected),
+            arrival: pick(&config.arrival, default.arrival),
+            global_warning: pick(&config.global_warning, default.global_warning),
+            size_small: pick(&config.size_small, default.size_small),
+            size_medium: pick(&config.size_medium, default.size_medium),
+            size_large: pick(&config.size_large, default.size_large),
+            muted: pick(&config.muted, default.muted),
+            input: pick(&config.input, default.input),
+            danger: pick(&config.danger, default.danger),
+            safe: pick(&config.safe, default.safe),
+            status_scanning: pick(&config.status_scanning, default.status_scanning),
+            status_browsing: pick(&config.status_browsing, default.status_browsing),
+            status_confirming: pick(&config.status_confirming, default.status_confirming),
+            status_deleting: pick(&config.status_deleting, default.status_deleting),
+            dialog: pick(&config.dialog, default.dialog),
+            dialog_bg: pick(&config.dialog_bg, default.dialog_bg),
+            cursor_modifier: default.cursor_modifier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_from_config() {
+        let config: ThemeConfig = toml::from_str(
+            r##"
+            header = "blue"
+            selected = "#005f87"
+            cursor = "dark grey"
+            muted = "244"
+            danger = "not-a-color"
+            "##,
+        )
+        .unwrap();
+
+        let theme = Theme::from(&config);
+        assert_eq!(theme.header, Color::Blue);
+        assert_eq!(theme.selected, Color::Rgb(0x00, 0x5f, 0x87));
+        assert_eq!(theme.cursor, Color::DarkGray);
+        assert_eq!(theme.muted, Color::Indexed(244));
+        // Unparseable and unset colors fall back to the defaults
+        assert_eq!(theme.danger, Color::Red);
+        assert_eq!(theme.status_confirming, Color::Magenta);
+    }
+}
@@ -1,5 +1,10 @@
 //! Shared utility functions
 
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
 /// Format bytes to human-readable size (e.g., "156.2 MB")
 pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -17,10 +22,194 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Format an age compactly, e.g. `45m`, `3d`, `2mo`, `1y`
+pub fn format_age(age: Duration) -> String {
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+
+    let secs = age.as_secs();
+    if secs < HOUR {
+        format!("{}m", secs / 60)
+    } else if secs < DAY {
+        format!("{}h", secs / HOUR)
+    } else if secs < 30 * DAY {
+        format!("{}d", secs / DAY)
+    } else if secs < 365 * DAY {
+        format!("{}mo", secs / (30 * DAY))
+    } else {
+        format!("{}y", secs / (365 * DAY))
+    }
+}
+
+/// Parse a human size like `10MB`, `500K`, `1.5 GB` or `2048` (bytes)
+///
+/// Units are binary (1 KB = 1024 bytes) and case-insensitive.
+pub fn parse_size(input: &str) -> anyhow::Result<u64> {
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+
+    let value: f64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid size '{}': expected e.g. 10MB or 500K", input))?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1024,
+        "M" | "MB" => 1024 * 1024,
+        "G" | "GB" => 1024 * 1024 * 1024,
+        "T" | "TB" => 1024 * 1024 * 1024 * 1024,
+        other => anyhow::bail!(
+            "Invalid size unit '{}' in '{}': use B, KB, MB, GB or TB",
+            other,
+            input
+        ),
+    };
+
+    Ok((value * multiplier as f64) as u64)
+}
+
+/// Parse a human duration like `30d`, `2w`, `6mo` or `1y`
+///
+/// Months are 30 days and years 365 days.
+pub fn parse_duration(input: &str) -> anyhow::Result<Duration> {
+    const DAY: u64 = 24 * 60 * 60;
+
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+
+    let value: u64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration '{}': expected e.g. 30d or 6mo", input))?;
+
+    let unit_secs = match unit.trim().to_ascii_lowercase().as_str() {
+        "h" => 60 * 60,
+        "d" => DAY,
+        "w" => 7 * DAY,
+        "mo" => 30 * DAY,
+        "y" => 365 * DAY,
+        other => anyhow::bail!(
+            "Invalid duration unit '{}' in '{}': use h, d, w, mo or y",
+            other,
+            input
+        ),
+    };
+
+    let secs = value
+        .checked_mul(unit_secs)
+        .ok_or_else(|| anyhow::anyhow!("Duration '{}' is out of range", input))?;
+    Ok(Duration::from_secs(secs))
+}
+
+/// Expand a leading `~` or `~/` to the home directory
+///
+/// `~user` forms and paths without a leading tilde are returned unchanged.
+pub fn expand_tilde(path: &Path) -> PathBuf {
+    let Some(path_str) = path.to_str() else {
+        return path.to_path_buf();
+    };
+
+    let rest = if path_str == "~" {
+        ""
+    } else if let Some(rest) = path_str
+        .strip_prefix("~/")
+        .or_else(|| path_str.strip_prefix("~\\"))
+    {
+        rest
+    } else {
+        return path.to_path_buf();
+    };
+
+    match dirs::home_dir() {
+        Some(home) if rest.is_empty() => home,
+        Some(home) => home.join(rest),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Write a file atomically by writing a sibling temp file and renaming it
+///
+/// Readers never observe a half-written file, even if the process dies mid-write.
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+/// Open a directory in the system file manager without waiting for it
+///
+/// Fails if the platform's opener (`open`, `explorer`, `xdg-open`) can't be started.
+pub fn open_in_file_manager(path: &Path) -> io::Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let mut child = std::process::Command::new(opener)
+        .arg(path)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    // Reap the opener once it exits
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Shorten `text` to at most `max_chars` characters by replacing its start
+/// with "..." (paths keep their most specific end)
+pub fn truncate_start(text: &str, max_chars: usize) -> String {
+    let len = text.chars().count();
+    if len <= max_chars {
+        return text.to_string();
+    }
+    let skip = len - max_chars.saturating_sub(3);
+    format!("...{}", text.chars().skip(skip).collect::<String>())
+}
+
+/// Render values as a one-line bar chart (e.g. "▁▃▅█"), scaled to the maximum
+pub fn sparkline(values: &[u64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            if max == 0 {
+                BARS[0]
+            } else {
+                BARS[((v as u128 * 7 + max as u128 / 2) / max as u128) as usize]
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_truncate_start() {
+        assert_eq!(truncate_start("/short", 10), "/short");
+        assert_eq!(truncate_start("/home/me/project", 10), "...project");
+        // Cuts on characters, not bytes
+        assert_eq!(truncate_start("/home/日本語/projét", 11), "...語/projét");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 35, 70]), "▁▅█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
     #[test]
     fn test_format_size_bytes() {
         assert_eq!(format_size(0), "0 B");
@@ -43,4 +232,77 @@ mod tests {
     fn test_format_size_gb() {
         assert_eq!(format_size(1024 * 1024 * 1024), "1.0 GB");
     }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("2048").unwrap(), 2048);
+        assert_eq!(parse_size("500K").unwrap(), 500 * 1024);
+        assert_eq!(parse_size("10MB").unwrap(), 10 * 1024 * 1024);
+        assert_eq!(parse_size("10mb").unwrap(), 10 * 1024 * 1024);
+        assert_eq!(parse_size("1.5 GB").unwrap(), 1536 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_size_rejects_malformed() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("MB").is_err());
+        assert!(parse_size("10XB").is_err());
+        assert!(parse_size("1.2.3MB").is_err());
+        assert!(parse_size("-5MB").is_err());
+    }
+
+    #[test]
+    fn test_format_age() {
+        let day = 24 * 60 * 60;
+        assert_eq!(format_age(Duration::from_secs(90)), "1m");
+        assert_eq!(format_age(Duration::from_secs(5 * 60 * 60)), "5h");
+        assert_eq!(format_age(Duration::from_secs(3 * day)), "3d");
+        assert_eq!(format_age(Duration::from_secs(65 * day)), "2mo");
+        assert_eq!(format_age(Duration::from_secs(400 * day)), "1y");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let day = 24 * 60 * 60;
+        assert_eq!(
+            parse_duration("30d").unwrap(),
+            Duration::from_secs(30 * day)
+        );
+        assert_eq!(parse_duration("2w").unwrap(), Duration::from_secs(14 * day));
+        assert_eq!(
+            parse_duration("6mo").unwrap(),
+            Duration::from_secs(180 * day)
+        );
+        assert_eq!(
+            parse_duration("1Y").unwrap(),
+            Duration::from_secs(365 * day)
+        );
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("99999999999999999y").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("5m").is_err());
+    }
+
+    #[test]
+    fn test_expand_tilde() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_tilde(Path::new("~")), home);
+        assert_eq!(expand_tilde(Path::new("~/Projects")), home.join("Projects"));
+        assert_eq!(
+            expand_tilde(Path::new("/abs/path")),
+            PathBuf::from("/abs/path")
+        );
+        assert_eq!(expand_tilde(Path::new("rel/~")), PathBuf::from("rel/~"));
+        assert_eq!(expand_tilde(Path::new("~user/x")), PathBuf::from("~user/x"));
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_content() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("out.json");
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert!(!temp.path().join("out.json.tmp").exists());
+    }
 }